@@ -0,0 +1,76 @@
+//! Compares the day 16 checksum for part 2 with building the whole disk, run with
+//! `cargo bench --bench day16`
+
+use advent_2016::day16::{generate_checksum, naive_checksum};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Passes everything through to the system allocator, keeping track of the most memory that
+/// was ever allocated at once
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const LENGTH: usize = 35651584;
+
+/// The fastest of a few runs, and the most memory allocated at once by any of them
+fn measure(checksum: fn(&str, usize) -> String, input: &str) -> (String, Duration, usize) {
+    let mut fastest = Duration::MAX;
+    let mut peak = 0;
+    let mut result = String::new();
+
+    for _ in 0..5 {
+        let before = ALLOCATED.load(Ordering::SeqCst);
+        PEAK.store(before, Ordering::SeqCst);
+
+        let start = Instant::now();
+        result = checksum(input, LENGTH);
+        fastest = fastest.min(start.elapsed());
+
+        peak = peak.max(PEAK.load(Ordering::SeqCst) - before);
+    }
+
+    (result, fastest, peak)
+}
+
+fn main() {
+    let input = include_str!("../input/2016/day16.txt");
+
+    let (naive, naive_time, naive_peak) = measure(naive_checksum, input);
+    let (streamed, streamed_time, streamed_peak) = measure(generate_checksum, input);
+
+    println!(
+        "building the disk: {:>12.2?} {:>12} bytes",
+        naive_time, naive_peak
+    );
+    println!(
+        "counting chunks:   {:>12.2?} {:>12} bytes",
+        streamed_time, streamed_peak
+    );
+
+    assert_eq!(naive, streamed);
+    assert!(
+        streamed_peak < 4096,
+        "counting chunks should only need memory for the initial state and the checksum"
+    );
+}
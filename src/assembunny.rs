@@ -0,0 +1,681 @@
+mod assembler;
+mod compiler;
+mod config;
+mod debugger;
+mod decompiler;
+mod optimiser;
+mod parser;
+
+pub use assembler::{assemble, assemble_with, disassemble, disassemble_with, DisassembleError};
+pub use compiler::{compile, Compiled, Op};
+pub use config::{Arithmetic, Config};
+pub use debugger::{Breakpoint, Condition, Debugger, Snapshot, Stop};
+pub use decompiler::decompile;
+pub use optimiser::optimise;
+pub use parser::{parse_program, parse_program_with, ParseError};
+use std::{
+    collections::HashMap, convert::TryFrom, error::Error, fmt, num::ParseIntError, str::FromStr,
+};
+
+pub type Register = usize;
+pub type Value = i64;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Raw(Value),
+    Register(Register),
+}
+
+/// Parses anything the operand's Display produces, so any letter (or `r` followed by the index)
+/// is accepted as a register. Use [`Config::operand`] to only accept a dialect's registers.
+impl FromStr for Operand {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        Ok(match (chars.next(), chars.next()) {
+            (Some(c @ 'a'..='z'), None) => Operand::Register(c as usize - 'a' as usize),
+            (Some('r'), Some('0'..='9')) => Operand::Register(s[1..].parse()?),
+            _ => Operand::Raw(s.parse()?),
+        })
+    }
+}
+
+/// Formats the operand as it would appear in the source, using `a`, `b`, `c`... for registers
+/// and then `r26`, `r27`... once the letters run out
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(None).fmt(f)
+    }
+}
+
+impl Operand {
+    /// Format the operand with register names from the config, falling back to the same names as
+    /// Display for any registers the config doesn't name
+    fn display_with(self, config: Option<&Config>) -> impl fmt::Display + '_ {
+        WithConfig {
+            value: self,
+            config,
+        }
+    }
+}
+
+/// Formats a value using the register names from a config, if there is one
+struct WithConfig<'a, T> {
+    value: T,
+    config: Option<&'a Config>,
+}
+
+impl fmt::Display for WithConfig<'_, Operand> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let register = match self.value {
+            Operand::Raw(x) => return write!(f, "{}", x),
+            Operand::Register(x) => x,
+        };
+
+        match self
+            .config
+            .and_then(|config| config.registers.get(register))
+        {
+            Some(name) => write!(f, "{}", name),
+            None if register < 26 => write!(f, "{}", (b'a' + register as u8) as char),
+            None => write!(f, "r{}", register),
+        }
+    }
+}
+
+impl fmt::Display for WithConfig<'_, &Instruction> {
+    /// Formats the instruction as assembunny source, with lower case mnemonics made up for the
+    /// synthetic instructions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let o = |x: Operand| x.display_with(self.config);
+        let r = |x: Register| o(Operand::Register(x));
+
+        match *self.value {
+            Instruction::Copy(x, y) => write!(f, "cpy {} {}", o(x), o(y)),
+            Instruction::Inc(x) => write!(f, "inc {}", o(x)),
+            Instruction::Dec(x) => write!(f, "dec {}", o(x)),
+            Instruction::JumpNonZero(x, y) => write!(f, "jnz {} {}", o(x), o(y)),
+            Instruction::Toggle(x) => write!(f, "tgl {}", o(x)),
+            Instruction::Out(x) => write!(f, "out {}", o(x)),
+            Instruction::Add(x, y) => write!(f, "add {} {}", r(x), r(y)),
+            Instruction::Mul(x, y, z, w) => {
+                write!(f, "mul {} {} {} {}", o(x), r(y), r(z), r(w))
+            }
+            Instruction::Nop => write!(f, "nop"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Copy(Operand, Operand),
+    Inc(Operand),
+    Dec(Operand),
+    JumpNonZero(Operand, Operand),
+    Toggle(Operand),
+    Out(Operand),
+
+    /// Synthetic: add register x to register y and clear x, replacing an `inc`/`dec`/`jnz` loop
+    Add(Register, Register),
+
+    /// Synthetic: add x multiplied by register y to register z, clearing y and the scratch
+    /// register w, replacing a nested `cpy`/`inc`/`dec`/`jnz` loop
+    ///
+    /// Stored as `Mul(x, y, z, w)`
+    Mul(Operand, Register, Register, Register),
+
+    /// Synthetic: the remainder of a loop which has been replaced by `Add` or `Mul`
+    Nop,
+}
+
+impl fmt::Display for Instruction {
+    /// Formats the instruction as assembunny source, see [`Instruction::display_with`] for
+    /// registers with other names
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        WithConfig {
+            value: self,
+            config: None,
+        }
+        .fmt(f)
+    }
+}
+
+impl Instruction {
+    /// Format the instruction as source for the dialect described by the config, so that it can
+    /// be parsed again with [`parse_program_with`]
+    pub fn display_with<'a>(&'a self, config: &'a Config) -> impl fmt::Display + 'a {
+        WithConfig {
+            value: self,
+            config: Some(config),
+        }
+    }
+
+    /// The instruction this one becomes when hit by a `tgl`
+    pub fn toggle(&self) -> Self {
+        match *self {
+            Instruction::Copy(x, y) => Instruction::JumpNonZero(x, y),
+            Instruction::Inc(x) => Instruction::Dec(x),
+            Instruction::Dec(x) => Instruction::Inc(x),
+            Instruction::JumpNonZero(x, y) => Instruction::Copy(x, y),
+            Instruction::Toggle(x) => Instruction::Inc(x),
+            Instruction::Out(x) => Instruction::Inc(x),
+            Instruction::Add(..) | Instruction::Mul(..) | Instruction::Nop => *self,
+        }
+    }
+}
+
+/// Receives the values transmitted by the `out` instruction
+pub trait OutputSink {
+    /// Accept a value, returning false to pause execution after the current instruction
+    fn write(&mut self, value: Value) -> bool;
+}
+
+impl OutputSink for Vec<Value> {
+    fn write(&mut self, value: Value) -> bool {
+        self.push(value);
+        true
+    }
+}
+
+/// Sink which throws away all output
+pub struct Discard;
+
+impl OutputSink for Discard {
+    fn write(&mut self, _value: Value) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RuntimeError {
+    /// The instruction at `pointer` tried to read a register which doesn't exist
+    InvalidRegisterRead { pointer: isize, register: Register },
+
+    /// The instruction at `pointer` tried to write to a raw value or a register which doesn't exist
+    InvalidRegisterWrite { pointer: isize, operand: Operand },
+
+    /// The instruction at `pointer` overflowed a register
+    Overflow { pointer: isize },
+
+    /// The program was still running after this many steps
+    StepLimitExceeded(usize),
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::InvalidRegisterRead { pointer, register } => write!(
+                f,
+                "instruction {} read from invalid register {}",
+                pointer, register
+            ),
+            RuntimeError::InvalidRegisterWrite { pointer, operand } => write!(
+                f,
+                "instruction {} wrote to invalid operand {:?}",
+                pointer, operand
+            ),
+            RuntimeError::Overflow { pointer } => write!(f, "instruction {} overflowed", pointer),
+            RuntimeError::StepLimitExceeded(steps) => {
+                write!(f, "program did not halt within {} steps", steps)
+            }
+        }
+    }
+}
+
+impl Error for RuntimeError {}
+
+/// How a run with [`Computer::execute_detecting`] ended
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The instruction pointer left the program after this many steps
+    Halted(usize),
+
+    /// The state first seen after `entry` steps was seen again `period` steps later, so the
+    /// program will never halt
+    Cycled { entry: usize, period: usize },
+
+    /// The program was still running without having repeated a state after this many steps
+    BudgetExhausted(usize),
+}
+
+#[derive(Debug)]
+pub struct Computer {
+    pub pointer: isize,
+    pub registers: Vec<Value>,
+    pub arithmetic: Arithmetic,
+
+    /// Report writes to raw values (e.g. `cpy 1 2` after a `tgl`) as errors
+    /// instead of skipping them as the puzzle requires
+    pub strict: bool,
+}
+
+impl Default for Computer {
+    /// A computer with the four registers `a` to `d`, see [`Config`] for others
+    fn default() -> Self {
+        Computer {
+            pointer: 0,
+            registers: vec![0; 4],
+            arithmetic: Arithmetic::default(),
+            strict: false,
+        }
+    }
+}
+
+impl Computer {
+    pub fn new() -> Self {
+        Computer::default()
+    }
+
+    /// Create a computer with register `a` set to the given value
+    pub fn with_a(a: Value) -> Self {
+        let mut computer = Computer::new();
+        computer.registers[0] = a;
+        computer
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid, discarding any output
+    pub fn execute(&mut self, instructions: &mut [Instruction]) -> Result<(), RuntimeError> {
+        self.execute_with_output(instructions, &mut Discard)
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid or the sink
+    /// asks to pause. Execution can be resumed by calling this again.
+    pub fn execute_with_output(
+        &mut self,
+        instructions: &mut [Instruction],
+        sink: &mut impl OutputSink,
+    ) -> Result<(), RuntimeError> {
+        while self.step(instructions, sink)? {}
+        Ok(())
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid, giving up
+    /// after the given number of steps
+    pub fn execute_limited(
+        &mut self,
+        instructions: &mut [Instruction],
+        max_steps: usize,
+    ) -> Result<(), RuntimeError> {
+        for _ in 0..max_steps {
+            if !self.step(instructions, &mut Discard)? {
+                return Ok(());
+            }
+        }
+
+        if self.is_halted(instructions) {
+            Ok(())
+        } else {
+            Err(RuntimeError::StepLimitExceeded(max_steps))
+        }
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid, the computer
+    /// returns to a state it has been in before, or the step budget runs out
+    ///
+    /// The state is the pointer, the registers and the program (which `tgl` can modify). Every
+    /// state is remembered, so memory use grows with the budget. On a cycle the computer is left
+    /// at the repeated state.
+    pub fn execute_detecting(
+        &mut self,
+        instructions: &mut [Instruction],
+        max_steps: usize,
+    ) -> Result<Outcome, RuntimeError> {
+        // programs only change on a `tgl`, so number each version rather than storing copies
+        let mut programs = HashMap::new();
+        programs.insert(instructions.to_vec(), 0);
+
+        let mut seen = HashMap::new();
+        let mut program = 0;
+
+        for steps in 0..=max_steps {
+            if self.is_halted(instructions) {
+                return Ok(Outcome::Halted(steps));
+            }
+
+            let state = (self.pointer, self.registers.clone(), program);
+
+            if let Some(entry) = seen.insert(state, steps) {
+                return Ok(Outcome::Cycled {
+                    entry,
+                    period: steps - entry,
+                });
+            }
+
+            if steps == max_steps {
+                break;
+            }
+
+            let toggle = matches!(instructions[self.pointer as usize], Instruction::Toggle(_));
+
+            self.step(instructions, &mut Discard)?;
+
+            if toggle {
+                let next = programs.len();
+                program = *programs.entry(instructions.to_vec()).or_insert(next);
+            }
+        }
+
+        Ok(Outcome::BudgetExhausted(max_steps))
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid, with hot loops
+    /// replaced by synthetic instructions (see [`optimise`])
+    ///
+    /// Execution falls back to the original instructions when a jump lands in the middle of a
+    /// replaced loop, and the program is re-optimised whenever `tgl` modifies it
+    pub fn execute_optimised(
+        &mut self,
+        instructions: &mut [Instruction],
+    ) -> Result<(), RuntimeError> {
+        let mut optimised = optimise(instructions);
+        let mut fused = false;
+
+        while !self.is_halted(instructions) {
+            let pointer = self.pointer as usize;
+
+            match optimised[pointer] {
+                Instruction::Add(x, _) if self.read(&Operand::Register(x))? > 0 => fused = true,
+                Instruction::Mul(x, y, _, _)
+                    if self.read(&x)? > 0 && self.read(&Operand::Register(y))? > 0 =>
+                {
+                    fused = true
+                }
+                Instruction::Nop if fused => {}
+                _ => {
+                    // not safe to take the shortcut, so run what was originally there
+                    fused = false;
+                    let toggle = matches!(instructions[pointer], Instruction::Toggle(_));
+
+                    self.step(instructions, &mut Discard)?;
+
+                    if toggle {
+                        optimised = optimise(instructions);
+                    }
+
+                    continue;
+                }
+            }
+
+            self.step(&mut optimised, &mut Discard)?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute the instructions until the instruction pointer is no longer valid, using a
+    /// pre-decoded version of the program (see [`compile`])
+    ///
+    /// Instructions which can't be compiled or would overflow are handed back to the interpreter,
+    /// and the program is recompiled whenever `tgl` modifies it
+    pub fn execute_compiled(
+        &mut self,
+        instructions: &mut [Instruction],
+    ) -> Result<(), RuntimeError> {
+        let registers = self.registers.len();
+        let mut compiled = compile(instructions, registers, self.arithmetic);
+
+        loop {
+            self.pointer = compiled.run(self.pointer, &mut self.registers);
+
+            if self.is_halted(instructions) {
+                return Ok(());
+            }
+
+            let toggle = matches!(instructions[self.pointer as usize], Instruction::Toggle(_));
+
+            self.step(instructions, &mut Discard)?;
+
+            if toggle {
+                compiled = compile(instructions, registers, self.arithmetic);
+            }
+        }
+    }
+
+    /// Check whether the instruction pointer has left the program
+    pub fn is_halted(&self, instructions: &[Instruction]) -> bool {
+        self.pointer < 0 || self.pointer as usize >= instructions.len()
+    }
+
+    /// Execute a single instruction, returning false if the program has halted or the sink paused
+    ///
+    /// Instructions which write to raw values (e.g. toggled into `cpy 1 2`) are skipped unless
+    /// the computer is strict
+    pub fn step(
+        &mut self,
+        instructions: &mut [Instruction],
+        sink: &mut impl OutputSink,
+    ) -> Result<bool, RuntimeError> {
+        if self.is_halted(instructions) {
+            return Ok(false);
+        }
+
+        match instructions[self.pointer as usize] {
+            Instruction::Copy(x, y) => {
+                let x = self.arithmetic.normalise(self.read(&x)?);
+                self.write(&y, x)?;
+            }
+            Instruction::Inc(x) => {
+                let value = self.add(self.read(&x)?, 1)?;
+                self.write(&x, value)?;
+            }
+            Instruction::Dec(x) => {
+                let value = self.add(self.read(&x)?, -1)?;
+                self.write(&x, value)?;
+            }
+            Instruction::JumpNonZero(x, y) => {
+                if self.read(&x)? != 0 {
+                    self.pointer = self.offset(self.read(&y)?);
+                    return Ok(true);
+                }
+            }
+            Instruction::Toggle(x) => {
+                let target = self.offset(self.read(&x)?);
+
+                if target >= 0 && (target as usize) < instructions.len() {
+                    let target = target as usize;
+                    instructions[target] = instructions[target].toggle();
+                }
+            }
+            Instruction::Out(x) => {
+                let resume = sink.write(self.read(&x)?);
+                self.pointer += 1;
+                return Ok(resume);
+            }
+            Instruction::Add(x, y) => {
+                let (x, y) = (Operand::Register(x), Operand::Register(y));
+                let sum = self.add(self.read(&x)?, self.read(&y)?)?;
+                self.write(&y, sum)?;
+                self.write(&x, 0)?;
+            }
+            Instruction::Mul(x, y, z, w) => {
+                let (y, z, w) = (
+                    Operand::Register(y),
+                    Operand::Register(z),
+                    Operand::Register(w),
+                );
+                let product = self.arithmetic.mul(self.read(&x)?, self.read(&y)?).ok_or(
+                    RuntimeError::Overflow {
+                        pointer: self.pointer,
+                    },
+                )?;
+                let product = self.add(self.read(&z)?, product)?;
+                self.write(&z, product)?;
+                self.write(&y, 0)?;
+                self.write(&w, 0)?;
+            }
+            Instruction::Nop => {}
+        }
+
+        self.pointer += 1;
+        Ok(true)
+    }
+
+    /// Add two values with the computer's arithmetic
+    fn add(&self, x: Value, y: Value) -> Result<Value, RuntimeError> {
+        self.arithmetic.add(x, y).ok_or(RuntimeError::Overflow {
+            pointer: self.pointer,
+        })
+    }
+
+    /// The pointer moved by the given offset, which is out of range rather than overflowing if
+    /// the offset is huge
+    fn offset(&self, offset: Value) -> isize {
+        let offset =
+            isize::try_from(offset).unwrap_or(if offset < 0 { isize::MIN } else { isize::MAX });
+        self.pointer.saturating_add(offset)
+    }
+
+    /// Dereference the operand to a value
+    fn read(&self, operand: &Operand) -> Result<Value, RuntimeError> {
+        match *operand {
+            Operand::Raw(x) => Ok(x),
+            Operand::Register(register) => {
+                self.registers
+                    .get(register)
+                    .copied()
+                    .ok_or(RuntimeError::InvalidRegisterRead {
+                        pointer: self.pointer,
+                        register,
+                    })
+            }
+        }
+    }
+
+    /// Write a value to the register the operand refers to
+    fn write(&mut self, operand: &Operand, value: Value) -> Result<(), RuntimeError> {
+        let error = RuntimeError::InvalidRegisterWrite {
+            pointer: self.pointer,
+            operand: *operand,
+        };
+
+        match *operand {
+            Operand::Raw(_) if self.strict => Err(error),
+            Operand::Raw(_) => Ok(()),
+            Operand::Register(register) => {
+                *self.registers.get_mut(register).ok_or(error)? = value;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute() {
+        let mut program = parse_program("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a").unwrap();
+        let mut computer = Computer::new();
+        computer.execute(&mut program).unwrap();
+        assert_eq!(computer.registers[0], 42);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut program =
+            parse_program("cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a").unwrap();
+        let mut computer = Computer::new();
+        computer.execute(&mut program).unwrap();
+        assert_eq!(computer.registers[0], 3);
+    }
+
+    #[test]
+    fn test_output() {
+        let mut program =
+            parse_program("cpy 3 a // start\nout a\ndec a\njnz a -2\n\nout b").unwrap();
+        let mut computer = Computer::new();
+        let mut output = Vec::new();
+        computer
+            .execute_with_output(&mut program, &mut output)
+            .unwrap();
+        assert_eq!(output, vec![3, 2, 1, 0]);
+        assert!(computer.is_halted(&program));
+    }
+
+    #[test]
+    fn test_invalid_register_write() {
+        // tgl turns the `jnz 1 2` into `cpy 1 2`
+        let program = parse_program("tgl 1\njnz 1 2\ninc a").unwrap();
+
+        let mut computer = Computer::new();
+        computer.execute(&mut program.clone()).unwrap();
+        assert_eq!(computer.registers[0], 1);
+
+        let mut computer = Computer::new();
+        computer.strict = true;
+        assert_eq!(
+            computer.execute(&mut program.clone()),
+            Err(RuntimeError::InvalidRegisterWrite {
+                pointer: 1,
+                operand: Operand::Raw(2)
+            })
+        );
+
+        let mut program = vec![Instruction::Inc(Operand::Register(4))];
+        assert_eq!(
+            Computer::new().execute(&mut program),
+            Err(RuntimeError::InvalidRegisterRead {
+                pointer: 0,
+                register: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_limited() {
+        let mut program = parse_program("inc a\njnz 1 -1").unwrap();
+        let mut computer = Computer::new();
+
+        assert_eq!(
+            computer.execute_limited(&mut program, 100),
+            Err(RuntimeError::StepLimitExceeded(100))
+        );
+        assert_eq!(computer.registers[0], 50);
+
+        let mut program = parse_program("inc a\ninc a").unwrap();
+        assert_eq!(Computer::new().execute_limited(&mut program, 2), Ok(()));
+    }
+
+    #[test]
+    fn test_execute_detecting() {
+        let mut program = parse_program("inc a\ninc a").unwrap();
+        assert_eq!(
+            Computer::new().execute_detecting(&mut program, 2),
+            Ok(Outcome::Halted(2))
+        );
+
+        let mut program = parse_program("cpy 3 a\ndec a\njnz 1 -1").unwrap();
+        let mut computer = Computer::new();
+        assert_eq!(
+            computer.execute_detecting(&mut program, 100),
+            Ok(Outcome::BudgetExhausted(100))
+        );
+
+        let mut program = parse_program("cpy 3 a\njnz a 2\ninc b\njnz 1 -2").unwrap();
+        let mut computer = Computer::new();
+        assert_eq!(
+            computer.execute_detecting(&mut program, 100),
+            Ok(Outcome::Cycled {
+                entry: 1,
+                period: 2
+            })
+        );
+        assert_eq!(computer.pointer, 1);
+    }
+
+    #[test]
+    fn test_execute_detecting_toggle() {
+        // the pointer and registers repeat every 2 steps, but the program only every 4
+        let mut program = parse_program("tgl 2\njnz 1 -1\njnz 1 -2").unwrap();
+        assert_eq!(
+            Computer::new().execute_detecting(&mut program, 100),
+            Ok(Outcome::Cycled {
+                entry: 0,
+                period: 4
+            })
+        );
+    }
+}
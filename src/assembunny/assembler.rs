@@ -0,0 +1,317 @@
+use super::parser::{parse_instruction, strip_comment, tokenise};
+use super::{Config, Instruction, Operand, ParseError, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt::{self, Write};
+
+/// An instruction which can't be written as assembunny source, e.g. `inc 1` after a toggle or
+/// the synthetic instructions from the optimiser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembleError {
+    /// Index of the instruction in the program
+    pub index: usize,
+
+    pub instruction: Instruction,
+}
+
+impl fmt::Display for DisassembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} (`{}`) has no assembunny source",
+            self.index, self.instruction
+        )
+    }
+}
+
+impl Error for DisassembleError {}
+
+/// Assemble a program which may use labels in place of the relative offsets taken by `jnz` and
+/// `tgl`, e.g.
+///
+/// ```text
+/// start:
+///     dec a       // comments are ignored
+///     jnz a start
+/// ```
+///
+/// Labels can be on their own line or before an instruction, and can't be register names.
+pub fn assemble(source: &str) -> Result<Vec<Instruction>, ParseError> {
+    assemble_with(source, &Config::default())
+}
+
+/// Assemble a program using the register names from the given config
+pub fn assemble_with(source: &str, config: &Config) -> Result<Vec<Instruction>, ParseError> {
+    // first pass to find where each label points
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let code = strip_comment(line);
+        let mut tokens = tokenise(code);
+
+        while let Some(&(column, token)) = tokens.first() {
+            let name = match token.strip_suffix(':') {
+                Some(name) => name,
+                None => break,
+            };
+
+            let is_valid = !name.is_empty()
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && config.operand(name).is_none();
+
+            if !is_valid || labels.insert(name, lines.len()).is_some() {
+                return Err(ParseError {
+                    line: index + 1,
+                    column,
+                    token: token.to_string(),
+                    expected: vec!["a unique label which isn't a register or integer"],
+                });
+            }
+
+            tokens.remove(0);
+        }
+
+        if !tokens.is_empty() {
+            lines.push((index + 1, code, tokens));
+        }
+    }
+
+    // second pass to resolve labels to offsets
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, (line, code, tokens))| {
+            let resolve = |label: &str| labels.get(label).map(|&to| to as Value - i as Value);
+            parse_instruction(*line, code, tokens, config, Some(&resolve))
+        })
+        .collect()
+}
+
+/// Print a program as assembunny source, with labels at every jump target which is inside the
+/// program, which can be re-assembled with [`assemble`]
+///
+/// Programs from the parser can always be printed, but toggling or optimising one can produce
+/// instructions the parser would reject, and those are reported as errors.
+pub fn disassemble(instructions: &[Instruction]) -> Result<String, DisassembleError> {
+    disassemble_with(instructions, &Config::default())
+}
+
+/// Print a program using the register names from the given config, which can be re-assembled
+/// with [`assemble_with`]
+pub fn disassemble_with(
+    instructions: &[Instruction],
+    config: &Config,
+) -> Result<String, DisassembleError> {
+    let target = |index: usize, offset: &Operand| match offset {
+        Operand::Raw(offset) => {
+            let target = index as isize + *offset as isize;
+
+            if target >= 0 && (target as usize) < instructions.len() {
+                Some(target as usize)
+            } else {
+                None
+            }
+        }
+        Operand::Register(_) => None,
+    };
+
+    let targets: BTreeSet<_> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instruction)| match instruction {
+            Instruction::JumpNonZero(_, y) => target(i, y),
+            _ => None,
+        })
+        .collect();
+
+    // labels are `L` and the index, unless that could be mistaken for a register
+    let mut prefix = String::from("L");
+
+    while config.registers.iter().any(|register| {
+        let index = register.strip_prefix(prefix.as_str());
+        index.is_some_and(|index| index.parse::<usize>().is_ok())
+    }) {
+        prefix.insert(0, '_');
+    }
+
+    let mut output = String::new();
+
+    for (i, instruction) in instructions.iter().enumerate() {
+        let has_source = match instruction {
+            Instruction::Copy(_, y) | Instruction::Inc(y) | Instruction::Dec(y) => {
+                matches!(y, Operand::Register(_))
+            }
+            Instruction::JumpNonZero(..) | Instruction::Toggle(_) | Instruction::Out(_) => true,
+            Instruction::Add(..) | Instruction::Mul(..) | Instruction::Nop => false,
+        };
+
+        if !has_source {
+            return Err(DisassembleError {
+                index: i,
+                instruction: *instruction,
+            });
+        }
+
+        if targets.contains(&i) {
+            writeln!(output, "{}{}:", prefix, i).unwrap();
+        }
+
+        match instruction {
+            Instruction::JumpNonZero(x, y) if target(i, y).is_some() => {
+                let x = x.display_with(Some(config));
+                writeln!(output, "    jnz {} {}{}", x, prefix, target(i, y).unwrap()).unwrap()
+            }
+            _ => writeln!(output, "    {}", instruction.display_with(config)).unwrap(),
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::parser::{INTEGER, LABEL, REGISTER};
+    use crate::assembunny::{parse_program, parse_program_with, Arithmetic, Computer, Register};
+
+    #[test]
+    fn test_assemble() {
+        let source = "
+            cpy 3 b
+            loop: inc a     // add one
+            dec b
+            jnz b loop
+            jnz 1 end
+            tgl end
+            end:";
+
+        assert_eq!(
+            assemble(source).unwrap(),
+            parse_program("cpy 3 b\ninc a\ndec b\njnz b -2\njnz 1 2\ntgl 1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let error = assemble("jnz a nowhere").unwrap_err();
+        assert_eq!(error.column, 7);
+        assert_eq!(error.expected, vec![REGISTER, INTEGER, LABEL]);
+
+        let error = assemble("x: inc a\n x: dec a").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = assemble("b: inc a").unwrap_err();
+        assert_eq!(error.token, "b:");
+    }
+
+    #[test]
+    fn test_display() {
+        let source = "cpy 41 a\ninc b\ndec c\njnz d -2\ntgl -1\nout a";
+        let program = parse_program(source).unwrap();
+
+        assert_eq!(
+            program
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            source
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = parse_program("cpy 3 b\ninc a\ndec b\njnz b -2\njnz 1 c\njnz 1 5").unwrap();
+
+        assert_eq!(
+            disassemble(&program).unwrap(),
+            "    cpy 3 b\nL1:\n    inc a\n    dec b\n    jnz b L1\n    jnz 1 c\n    jnz 1 5\n"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_errors() {
+        // toggling the `tgl` turns it into `inc 1`, which can't be parsed
+        let mut program = parse_program("tgl 1\ntgl 1\njnz 1 2").unwrap();
+        Computer::new().execute(&mut program).unwrap();
+
+        assert_eq!(
+            disassemble(&program),
+            Err(DisassembleError {
+                index: 1,
+                instruction: Instruction::Inc(Operand::Raw(1)),
+            })
+        );
+
+        let error = disassemble(&[Instruction::Nop]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "instruction 0 (`nop`) has no assembunny source"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            include_str!("../../input/2016/day12.txt"),
+            include_str!("../../input/2016/day23.txt"),
+            include_str!("../../input/2016/day25.txt"),
+        ];
+
+        for input in inputs.iter() {
+            let program = parse_program(input).unwrap();
+
+            let source = disassemble(&program).unwrap();
+            assert_eq!(assemble(&source).unwrap(), program);
+
+            let source = program
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert_eq!(parse_program(&source).unwrap(), program);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_config() {
+        // a register which looks like a label, and more registers than there are letters
+        let mut names = vec!["acc", "L1", "tmp"];
+        let extra = (3..40).map(|i| format!("x{}", i)).collect::<Vec<_>>();
+        names.extend(extra.iter().map(String::as_str));
+        let config = Config::new(&names, Arithmetic::Checked);
+
+        let source = "cpy 3 L1\ninc acc\ndec L1\njnz L1 -2\ncpy acc x39\njnz x39 -5\nout tmp";
+        let program = parse_program_with(source, &config).unwrap();
+
+        let disassembled = disassemble_with(&program, &config).unwrap();
+        assert_eq!(
+            disassembled,
+            "_L0:\n    cpy 3 L1\n_L1:\n    inc acc\n    dec L1\n    jnz L1 _L1\n    cpy acc x39\n    \
+             jnz x39 _L0\n    out tmp\n"
+        );
+        assert_eq!(assemble_with(&disassembled, &config).unwrap(), program);
+
+        let source = program
+            .iter()
+            .map(|i| i.display_with(&config).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(parse_program_with(&source, &config).unwrap(), program);
+    }
+
+    #[test]
+    fn test_display_operands() {
+        for register in [0, 3, 25, 26, 159, 1000] {
+            let operand = Operand::Register(register as Register);
+            assert_eq!(operand.to_string().parse(), Ok(operand));
+        }
+
+        assert_eq!(Operand::Register(25).to_string(), "z");
+        assert_eq!(Operand::Register(26).to_string(), "r26");
+        assert_eq!("-12".parse(), Ok(Operand::Raw(-12)));
+        assert_eq!("r".parse(), Ok(Operand::Register(17)));
+        assert!("rx".parse::<Operand>().is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use super::{Arithmetic, Instruction, Operand, Register, Value};
+
+/// A pre-decoded instruction with operands resolved to register indices or constants and jump
+/// offsets resolved to absolute targets
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Op {
+    CopyValue(Value, Register),
+    CopyRegister(Register, Register),
+    Inc(Register),
+    Dec(Register),
+
+    /// Jump to the absolute target if the register is non-zero
+    JumpNonZero(Register, isize),
+
+    /// Jump to the absolute target
+    Jump(isize),
+
+    /// Do nothing, e.g. `jnz 0 x`
+    Nop,
+
+    /// Hand back to the interpreter to execute the original instruction, e.g. for `tgl`
+    Interpret,
+}
+
+/// A program lowered to [`Op`]s, ready to run without decoding operands on every step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compiled {
+    ops: Vec<Op>,
+    arithmetic: Arithmetic,
+}
+
+/// Lower each instruction to its specialised [`Op`], for a computer with the given number of
+/// registers and arithmetic
+///
+/// Anything which can't be pre-decoded (`tgl`, `out`, jumps by a register offset, writes to raw
+/// values etc.) becomes [`Op::Interpret`] so that the interpreter handles it instead.
+pub fn compile(instructions: &[Instruction], registers: usize, arithmetic: Arithmetic) -> Compiled {
+    let ops = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, instruction)| lower(i as isize, instruction, registers, arithmetic))
+        .collect();
+
+    Compiled { ops, arithmetic }
+}
+
+fn lower(index: isize, instruction: &Instruction, registers: usize, arithmetic: Arithmetic) -> Op {
+    use Operand::{Raw, Register as R};
+
+    let valid = |register: &Register| *register < registers;
+    let offset = |offset: Value| index.saturating_add(offset as isize);
+
+    match *instruction {
+        Instruction::Copy(Raw(x), R(y)) if valid(&y) && arithmetic.contains(x) => {
+            Op::CopyValue(x, y)
+        }
+        Instruction::Copy(R(x), R(y)) if valid(&x) && valid(&y) => Op::CopyRegister(x, y),
+        Instruction::Inc(R(x)) if valid(&x) => Op::Inc(x),
+        Instruction::Dec(R(x)) if valid(&x) => Op::Dec(x),
+        Instruction::JumpNonZero(Raw(0), _) => Op::Nop,
+        Instruction::JumpNonZero(Raw(_), Raw(y)) => Op::Jump(offset(y)),
+        Instruction::JumpNonZero(R(x), Raw(y)) if valid(&x) => Op::JumpNonZero(x, offset(y)),
+        Instruction::Nop => Op::Nop,
+        _ => Op::Interpret,
+    }
+}
+
+impl Compiled {
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Run from the given pointer until the program halts or reaches an instruction which needs
+    /// interpreting, returning the pointer it stopped at
+    ///
+    /// Increments and decrements which would overflow are also left to the interpreter.
+    pub fn run(&self, mut pointer: isize, registers: &mut [Value]) -> isize {
+        let arithmetic = self.arithmetic;
+        let step = |x: Value, y: Value| x.checked_add(y).filter(|&v| arithmetic.contains(v));
+
+        while pointer >= 0 && (pointer as usize) < self.ops.len() {
+            match self.ops[pointer as usize] {
+                Op::CopyValue(x, y) => registers[y] = x,
+                Op::CopyRegister(x, y) => registers[y] = registers[x],
+                Op::Inc(x) => match step(registers[x], 1) {
+                    Some(value) => registers[x] = value,
+                    None => break,
+                },
+                Op::Dec(x) => match step(registers[x], -1) {
+                    Some(value) => registers[x] = value,
+                    None => break,
+                },
+                Op::JumpNonZero(x, target) => {
+                    if registers[x] != 0 {
+                        pointer = target;
+                        continue;
+                    }
+                }
+                Op::Jump(target) => {
+                    pointer = target;
+                    continue;
+                }
+                Op::Nop => {}
+                Op::Interpret => break,
+            }
+
+            pointer += 1;
+        }
+
+        pointer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{parse_program, Computer};
+
+    #[test]
+    fn test_compile() {
+        let program =
+            parse_program("cpy 41 a\ncpy a b\njnz b -1\njnz 1 2\njnz 0 a\ntgl a\nout b").unwrap();
+
+        assert_eq!(
+            compile(&program, 4, Arithmetic::Checked).ops(),
+            &[
+                Op::CopyValue(41, 0),
+                Op::CopyRegister(0, 1),
+                Op::JumpNonZero(1, 1),
+                Op::Jump(5),
+                Op::Nop,
+                Op::Interpret,
+                Op::Interpret,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_compiled() {
+        // the `tgl` turns `jnz 1 -2` into `cpy 1 -2` and hands back to the interpreter to skip it
+        let source = "cpy 3 a\ninc b\ndec a\ntgl 1\njnz 1 -2\njnz a -4\ninc c";
+
+        let mut interpreted = Computer::new();
+        interpreted
+            .execute(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        let mut program = parse_program(source).unwrap();
+        let mut compiled = Computer::new();
+        compiled.execute_compiled(&mut program).unwrap();
+
+        assert_eq!(compiled.registers, interpreted.registers);
+        assert_eq!(compiled.registers, [0, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,158 @@
+use super::{Computer, Operand, Register, Value};
+
+/// How register arithmetic behaves when it overflows
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Arithmetic {
+    /// Wrap around within the range of an `i32`
+    Wrapping32,
+
+    /// Wrap around within the range of an `i64`
+    Wrapping64,
+
+    /// Report going outside the range of an `i64` as an error
+    #[default]
+    Checked,
+}
+
+impl Arithmetic {
+    /// Add two values, or `None` if the result overflows
+    pub fn add(self, x: Value, y: Value) -> Option<Value> {
+        match self {
+            Arithmetic::Wrapping32 => Some((x as i32).wrapping_add(y as i32) as Value),
+            Arithmetic::Wrapping64 => Some(x.wrapping_add(y)),
+            Arithmetic::Checked => x.checked_add(y),
+        }
+    }
+
+    /// Multiply two values, or `None` if the result overflows
+    pub fn mul(self, x: Value, y: Value) -> Option<Value> {
+        match self {
+            Arithmetic::Wrapping32 => Some((x as i32).wrapping_mul(y as i32) as Value),
+            Arithmetic::Wrapping64 => Some(x.wrapping_mul(y)),
+            Arithmetic::Checked => x.checked_mul(y),
+        }
+    }
+
+    /// Bring a value (e.g. a constant from the program) into range
+    pub fn normalise(self, x: Value) -> Value {
+        match self {
+            Arithmetic::Wrapping32 => x as i32 as Value,
+            Arithmetic::Wrapping64 | Arithmetic::Checked => x,
+        }
+    }
+
+    /// Check whether a value is already in range
+    pub fn contains(self, x: Value) -> bool {
+        self.normalise(x) == x
+    }
+}
+
+/// Describes a dialect of assembunny, i.e. the names of its registers and how they overflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Register names, in index order
+    pub registers: Vec<String>,
+    pub arithmetic: Arithmetic,
+}
+
+impl Default for Config {
+    /// The four registers `a` to `d` from the puzzles
+    fn default() -> Self {
+        Config::new(&["a", "b", "c", "d"], Arithmetic::default())
+    }
+}
+
+impl Config {
+    pub fn new(registers: &[&str], arithmetic: Arithmetic) -> Self {
+        Config {
+            registers: registers.iter().map(|r| r.to_string()).collect(),
+            arithmetic,
+        }
+    }
+
+    /// Look up a register by name
+    pub fn register(&self, name: &str) -> Option<Register> {
+        self.registers.iter().position(|r| r == name)
+    }
+
+    /// Parse an operand as either a register name or an integer
+    pub fn operand(&self, s: &str) -> Option<Operand> {
+        match self.register(s) {
+            Some(register) => Some(Operand::Register(register)),
+            None => s.parse().ok().map(Operand::Raw),
+        }
+    }
+
+    /// Create a computer with these registers and arithmetic
+    pub fn computer(&self) -> Computer {
+        Computer {
+            registers: vec![0; self.registers.len()],
+            arithmetic: self.arithmetic,
+            ..Computer::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{parse_program_with, RuntimeError};
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(
+            Arithmetic::Wrapping32.add(i32::MAX as Value, 1),
+            Some(i32::MIN as Value)
+        );
+        assert_eq!(Arithmetic::Wrapping64.add(i64::MAX, 1), Some(i64::MIN));
+        assert_eq!(Arithmetic::Checked.add(i64::MAX, 1), None);
+
+        assert_eq!(Arithmetic::Wrapping32.mul(1 << 16, 1 << 16), Some(0));
+        assert_eq!(Arithmetic::Checked.mul(1 << 32, 1 << 32), None);
+
+        assert_eq!(Arithmetic::Wrapping32.normalise(1 << 32), 0);
+        assert!(!Arithmetic::Wrapping32.contains(1 << 32));
+        assert!(Arithmetic::Checked.contains(1 << 32));
+    }
+
+    #[test]
+    fn test_custom_registers() {
+        let config = Config::new(&["x", "y", "z", "acc", "tmp"], Arithmetic::Checked);
+        let mut program =
+            parse_program_with("cpy 5 tmp\ninc acc\ndec tmp\njnz tmp -2", &config).unwrap();
+
+        let mut computer = config.computer();
+        computer.execute(&mut program).unwrap();
+
+        assert_eq!(computer.registers, vec![0, 0, 0, 5, 0]);
+        assert!(parse_program_with("inc a", &config).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let source = "cpy 2147483647 a\ninc a";
+
+        let config = Config::new(&["a"], Arithmetic::Wrapping32);
+        let mut computer = config.computer();
+        computer
+            .execute(&mut parse_program_with(source, &config).unwrap())
+            .unwrap();
+        assert_eq!(computer.registers, vec![i32::MIN as Value]);
+
+        let source = "cpy 9223372036854775807 a\ninc a";
+
+        let config = Config::new(&["a"], Arithmetic::Wrapping64);
+        let mut computer = config.computer();
+        computer
+            .execute_compiled(&mut parse_program_with(source, &config).unwrap())
+            .unwrap();
+        assert_eq!(computer.registers, vec![i64::MIN]);
+
+        let config = Config::new(&["a"], Arithmetic::Checked);
+        let mut computer = config.computer();
+        assert_eq!(
+            computer.execute_compiled(&mut parse_program_with(source, &config).unwrap()),
+            Err(RuntimeError::Overflow { pointer: 1 })
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use super::{Computer, Instruction, Register, RuntimeError, Value};
+use std::fmt;
+
+/// The state of the computer between two instructions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    pub pointer: isize,
+    pub registers: Vec<Value>,
+}
+
+impl From<&Computer> for Snapshot {
+    fn from(computer: &Computer) -> Self {
+        Snapshot {
+            pointer: computer.pointer,
+            registers: computer.registers.clone(),
+        }
+    }
+}
+
+/// A predicate over the registers
+pub type Condition = Box<dyn Fn(&[Value]) -> bool>;
+
+/// A condition which pauses execution when [`Debugger::run`] reaches it
+pub enum Breakpoint {
+    /// Pause before executing the instruction at this index
+    Instruction(usize),
+
+    /// Pause before executing any instruction once the register has this value, never hit if
+    /// the computer doesn't have the register
+    Register(Register, Value),
+
+    /// Pause before executing any instruction once the predicate over the registers holds
+    Condition(Condition),
+}
+
+impl Breakpoint {
+    fn is_hit(&self, computer: &Computer) -> bool {
+        match self {
+            Breakpoint::Instruction(index) => computer.pointer == *index as isize,
+            Breakpoint::Register(register, value) => {
+                computer.registers.get(*register) == Some(value)
+            }
+            Breakpoint::Condition(predicate) => predicate(&computer.registers),
+        }
+    }
+}
+
+impl fmt::Debug for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Instruction(index) => f.debug_tuple("Instruction").field(index).finish(),
+            Breakpoint::Register(register, value) => f
+                .debug_tuple("Register")
+                .field(register)
+                .field(value)
+                .finish(),
+            Breakpoint::Condition(_) => f.write_str("Condition(..)"),
+        }
+    }
+}
+
+/// Why [`Debugger::run`] stopped
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stop {
+    /// The instruction pointer left the program
+    Halted,
+
+    /// The breakpoint at this index in the debugger's list was hit
+    Breakpoint(usize),
+
+    /// The instruction at the current pointer failed
+    Fault(RuntimeError),
+}
+
+/// Steps a computer through a program one instruction at a time, keeping
+/// track of how often each instruction is executed
+pub struct Debugger {
+    pub computer: Computer,
+    pub instructions: Vec<Instruction>,
+    pub output: Vec<Value>,
+    breakpoints: Vec<Breakpoint>,
+    hits: Vec<usize>,
+}
+
+impl Debugger {
+    pub fn new(computer: Computer, instructions: &[Instruction]) -> Self {
+        Debugger {
+            computer,
+            instructions: instructions.to_vec(),
+            output: Vec::new(),
+            breakpoints: Vec::new(),
+            hits: vec![0; instructions.len()],
+        }
+    }
+
+    /// Add a breakpoint, returning its index for matching against [`Stop::Breakpoint`]
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    /// Remove all breakpoints
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Execute a single instruction, returning the state afterwards or `None` if already halted
+    pub fn step(&mut self) -> Result<Option<Snapshot>, RuntimeError> {
+        if self.computer.is_halted(&self.instructions) {
+            return Ok(None);
+        }
+
+        let pointer = self.computer.pointer as usize;
+        self.computer
+            .step(&mut self.instructions, &mut self.output)?;
+        self.hits[pointer] += 1;
+
+        Ok(Some(Snapshot::from(&self.computer)))
+    }
+
+    /// Execute at least one instruction, then keep going until a breakpoint is hit, the program
+    /// halts or an instruction fails
+    pub fn run(&mut self) -> Stop {
+        match self.step() {
+            Ok(None) => return Stop::Halted,
+            Err(e) => return Stop::Fault(e),
+            Ok(Some(_)) => {}
+        }
+
+        loop {
+            if self.computer.is_halted(&self.instructions) {
+                return Stop::Halted;
+            }
+
+            let hit = self
+                .breakpoints
+                .iter()
+                .position(|b| b.is_hit(&self.computer));
+
+            if let Some(index) = hit {
+                return Stop::Breakpoint(index);
+            }
+
+            if let Err(e) = self.step() {
+                return Stop::Fault(e);
+            }
+        }
+    }
+
+    /// Iterate over the state after each executed instruction until the program halts
+    ///
+    /// The iterator also ends if an instruction fails, which [`Debugger::step`] will then report
+    pub fn trace(&mut self) -> impl Iterator<Item = Snapshot> + '_ {
+        std::iter::from_fn(move || self.step().ok().flatten())
+    }
+
+    /// The number of times each instruction has been executed so far
+    pub fn profile(&self) -> &[usize] {
+        &self.hits
+    }
+
+    /// The indices and hit counts of the most executed instructions, hottest first
+    pub fn hotspots(&self, count: usize) -> Vec<(usize, usize)> {
+        let mut hotspots: Vec<_> = self.hits.iter().cloned().enumerate().collect();
+        hotspots.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hotspots.truncate(count);
+        hotspots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{parse_program, Operand};
+
+    static PROGRAM: &str = "cpy 3 b\ninc a\ndec b\njnz b -2\nout a";
+
+    #[test]
+    fn test_step() {
+        let program = parse_program(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(Computer::new(), &program);
+
+        assert_eq!(
+            debugger.step(),
+            Ok(Some(Snapshot {
+                pointer: 1,
+                registers: vec![0, 3, 0, 0]
+            }))
+        );
+        assert_eq!(
+            debugger.step(),
+            Ok(Some(Snapshot {
+                pointer: 2,
+                registers: vec![1, 3, 0, 0]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let program = parse_program(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(Computer::new(), &program);
+
+        let register = debugger.add_breakpoint(Breakpoint::Register(0, 2));
+        assert_eq!(debugger.run(), Stop::Breakpoint(register));
+        assert_eq!(debugger.computer.registers, [2, 2, 0, 0]);
+
+        // the register condition still holds, so hits again after the next step
+        assert_eq!(debugger.run(), Stop::Breakpoint(register));
+        assert_eq!(debugger.computer.registers, [2, 1, 0, 0]);
+
+        debugger.clear_breakpoints();
+        let line = debugger.add_breakpoint(Breakpoint::Instruction(4));
+        assert_eq!(debugger.run(), Stop::Breakpoint(line));
+        assert_eq!(debugger.computer.registers, [3, 0, 0, 0]);
+
+        assert_eq!(debugger.run(), Stop::Halted);
+        assert_eq!(debugger.output, vec![3]);
+        assert_eq!(debugger.run(), Stop::Halted);
+    }
+
+    #[test]
+    fn test_condition() {
+        let program = parse_program(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(Computer::new(), &program);
+
+        debugger.add_breakpoint(Breakpoint::Condition(Box::new(|r| r[0] > r[1])));
+
+        assert_eq!(debugger.run(), Stop::Breakpoint(0));
+        assert_eq!(debugger.computer.registers, [2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_missing_register() {
+        let program = parse_program(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(Computer::new(), &program);
+
+        debugger.add_breakpoint(Breakpoint::Register(4, 0));
+
+        assert_eq!(debugger.run(), Stop::Halted);
+        assert_eq!(debugger.output, vec![3]);
+    }
+
+    #[test]
+    fn test_trace_and_profile() {
+        let program = parse_program(PROGRAM).unwrap();
+        let mut debugger = Debugger::new(Computer::new(), &program);
+
+        let pointers: Vec<_> = debugger.trace().map(|s| s.pointer).collect();
+        assert_eq!(pointers, vec![1, 2, 3, 1, 2, 3, 1, 2, 3, 4, 5]);
+
+        assert_eq!(debugger.profile(), &[1, 3, 3, 3, 1]);
+        assert_eq!(debugger.hotspots(2), vec![(1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn test_fault() {
+        let program = parse_program("tgl 1\njnz 1 2\ninc a").unwrap();
+        let mut computer = Computer::new();
+        computer.strict = true;
+
+        let mut debugger = Debugger::new(computer, &program);
+
+        assert_eq!(debugger.trace().count(), 1);
+        assert_eq!(
+            debugger.run(),
+            Stop::Fault(RuntimeError::InvalidRegisterWrite {
+                pointer: 1,
+                operand: Operand::Raw(2)
+            })
+        );
+    }
+}
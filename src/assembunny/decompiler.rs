@@ -0,0 +1,524 @@
+use super::{optimise, Instruction, Operand, Register};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// A `jnz` condition, i.e. whether an operand is zero or not
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Condition {
+    operand: Operand,
+    non_zero: bool,
+}
+
+impl Condition {
+    fn negate(self) -> Self {
+        Condition {
+            non_zero: !self.non_zero,
+            ..self
+        }
+    }
+
+    /// Whether the condition is known ahead of time, e.g. `jnz 1 x` always jumps
+    fn constant(&self) -> Option<bool> {
+        match self.operand {
+            Operand::Raw(x) => Some((x != 0) == self.non_zero),
+            Operand::Register(_) => None,
+        }
+    }
+
+    fn render(&self) -> String {
+        let op = if self.non_zero { "!=" } else { "==" };
+        format!("{} {} 0", operand(&self.operand), op)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Statement {
+    /// A single line of straight-line code
+    Line(String),
+    Label(usize),
+    Goto(usize),
+    Break(Option<usize>),
+    Continue(Option<usize>),
+    If(Condition, Vec<Statement>),
+    Loop(usize, Vec<Statement>),
+    While(usize, Condition, Vec<Statement>),
+}
+
+/// A loop which is currently being decompiled, spanning `start..=end`
+#[derive(Debug, Copy, Clone)]
+struct Frame {
+    start: usize,
+    end: usize,
+}
+
+struct Decompiler {
+    instructions: Vec<Instruction>,
+
+    /// The source and destination of every `jnz` with a constant offset
+    jumps: Vec<(usize, usize)>,
+
+    /// Loops (by start index) which need a label because something breaks out of them or
+    /// continues them from further in
+    labelled_loops: HashSet<usize>,
+
+    /// Instructions which are the target of a `goto`
+    labelled_lines: HashSet<usize>,
+}
+
+/// Decompile an assembunny program to structured pseudo-Rust
+///
+/// Arithmetic loops are recovered by the [`optimise`] pass, then control flow is rebuilt from
+/// the `jnz` targets into loops, `if`s, `break`s and `continue`s. Anything which can't be
+/// nested cleanly falls back to labels and `goto`s.
+///
+/// This is a static view of the program, so it doesn't account for any later `tgl` and can only
+/// follow `jnz` instructions with constant offsets.
+pub fn decompile(instructions: &[Instruction]) -> String {
+    let instructions = recover_arithmetic(instructions);
+
+    let jumps = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instruction)| jump_target(i, instruction).map(|target| (i, target)))
+        .collect();
+
+    let mut decompiler = Decompiler {
+        instructions,
+        jumps,
+        labelled_loops: HashSet::new(),
+        labelled_lines: HashSet::new(),
+    };
+
+    let statements = decompiler.block(0, decompiler.instructions.len(), &[]);
+
+    let mut output = String::new();
+    decompiler.render(&statements, 0, &mut output);
+    output
+}
+
+/// Where a `jnz` with a constant offset may jump to
+fn jump_target(index: usize, instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::JumpNonZero(x, Operand::Raw(offset)) if *x != Operand::Raw(0) => {
+            Some((index as isize + *offset as isize) as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Replace add and multiply loops with their synthetic instructions, as long as nothing outside
+/// of them jumps into the middle
+fn recover_arithmetic(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut optimised = optimise(instructions);
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        let targets: Vec<_> = optimised
+            .iter()
+            .enumerate()
+            .filter_map(|(i, instruction)| jump_target(i, instruction))
+            .filter(|&target| optimised.get(target) == Some(&Instruction::Nop))
+            .collect();
+
+        for target in targets {
+            let mut head = target;
+            while optimised[head] == Instruction::Nop {
+                head -= 1;
+            }
+
+            optimised[head] = instructions[head];
+            head += 1;
+
+            while head < optimised.len() && optimised[head] == Instruction::Nop {
+                optimised[head] = instructions[head];
+                head += 1;
+            }
+
+            changed = true;
+        }
+    }
+
+    optimised
+}
+
+impl Decompiler {
+    /// Check nothing outside of `start..=end` jumps into `first..=end`
+    fn is_closed(&self, start: usize, first: usize, end: usize) -> bool {
+        self.jumps
+            .iter()
+            .filter(|(from, _)| *from < start || *from > end)
+            .all(|(_, to)| *to < first || *to > end)
+    }
+
+    /// Decompile the instructions in `start..end`
+    fn block(&mut self, start: usize, end: usize, loops: &[Frame]) -> Vec<Statement> {
+        let mut statements = Vec::new();
+        let mut i = start;
+
+        while i < end {
+            if self.jumps.iter().any(|(_, to)| *to == i) {
+                statements.push(Statement::Label(i));
+            }
+
+            // the outermost backwards jump to here which nests properly is a loop
+            let closing = self
+                .jumps
+                .iter()
+                .filter(|(from, to)| *to == i && *from >= i && *from < end)
+                .map(|(from, _)| *from)
+                .filter(|&from| self.is_closed(i, i + 1, from))
+                .max();
+
+            if let Some(j) = closing {
+                statements.push(self.loop_statement(i, j, loops));
+                i = j + 1;
+                continue;
+            }
+
+            match self.instructions[i] {
+                Instruction::JumpNonZero(x, Operand::Raw(offset)) if x != Operand::Raw(0) => {
+                    let target = (i as isize + offset as isize) as usize;
+                    let condition = Condition {
+                        operand: x,
+                        non_zero: true,
+                    };
+
+                    // skipping forwards over a block is an `if` around that block
+                    let is_if = condition.constant().is_none()
+                        && target > i + 1
+                        && target <= end
+                        && self.is_closed(i, i + 1, target - 1);
+
+                    if is_if {
+                        let body = self.block(i + 1, target, loops);
+                        statements.push(Statement::If(condition.negate(), body));
+                        i = target;
+                        continue;
+                    }
+
+                    let jump = self.jump(i, target, loops);
+                    statements.push(match condition.constant() {
+                        Some(_) => jump,
+                        None => Statement::If(condition, vec![jump]),
+                    });
+                }
+                instruction => {
+                    statements.extend(line(instruction).into_iter().map(Statement::Line))
+                }
+            }
+
+            i += 1;
+        }
+
+        statements
+    }
+
+    /// Decompile the loop `start..=end`, where `end` is the `jnz` which closes it
+    fn loop_statement(&mut self, start: usize, end: usize, loops: &[Frame]) -> Statement {
+        let mut frames = loops.to_vec();
+        frames.push(Frame { start, end });
+
+        let mut body = self.block(start, end, &frames);
+
+        // the closing `jnz` isn't part of the body, so it needs its label adding if anything
+        // inside has to `goto` it
+        if self.labelled_lines.contains(&end) {
+            body.push(Statement::Label(end));
+        }
+
+        if let Instruction::JumpNonZero(x, _) = self.instructions[end] {
+            let condition = Condition {
+                operand: x,
+                non_zero: true,
+            };
+
+            if condition.constant().is_none() {
+                body.push(Statement::If(
+                    condition.negate(),
+                    vec![Statement::Break(None)],
+                ));
+            }
+        }
+
+        // a loop which starts by checking whether to break is a while loop
+        if body.first() == Some(&Statement::Label(start)) {
+            body.remove(0);
+        }
+
+        match body.first() {
+            Some(Statement::If(condition, inner)) if inner == &[Statement::Break(None)] => {
+                let condition = condition.negate();
+                body.remove(0);
+                Statement::While(start, condition, body)
+            }
+            _ => Statement::Loop(start, body),
+        }
+    }
+
+    /// Work out how to get from the `jnz` at `from` to `target`
+    fn jump(&mut self, from: usize, target: usize, loops: &[Frame]) -> Statement {
+        let innermost = loops.last().map(|frame| frame.start);
+
+        for frame in loops.iter().rev() {
+            let label = Some(frame.start).filter(|&start| Some(start) != innermost);
+
+            // jumping to a closing `jnz` which always jumps is the same as going back to the start
+            let always_loops = match self.instructions[frame.end] {
+                Instruction::JumpNonZero(x, _) => {
+                    let condition = Condition {
+                        operand: x,
+                        non_zero: true,
+                    };
+                    condition.constant() == Some(true)
+                }
+                _ => false,
+            };
+
+            let statement = if target == frame.end + 1 {
+                Statement::Break(label)
+            } else if (target == frame.start && from != frame.end)
+                || (target == frame.end && always_loops)
+            {
+                Statement::Continue(label)
+            } else {
+                continue;
+            };
+
+            if let Some(start) = label {
+                self.labelled_loops.insert(start);
+            }
+
+            return statement;
+        }
+
+        self.labelled_lines.insert(target);
+        Statement::Goto(target)
+    }
+
+    fn render(&self, statements: &[Statement], depth: usize, output: &mut String) {
+        let indent = "    ".repeat(depth);
+
+        let label = |start: &usize| {
+            if self.labelled_loops.contains(start) {
+                format!("'l{}: ", start)
+            } else {
+                String::new()
+            }
+        };
+
+        for statement in statements {
+            match statement {
+                Statement::Line(line) if line.ends_with('}') => {
+                    writeln!(output, "{}{}", indent, line).unwrap()
+                }
+                Statement::Line(line) => writeln!(output, "{}{};", indent, line).unwrap(),
+                Statement::Label(target) => {
+                    if self.labelled_lines.contains(target) {
+                        writeln!(output, "L{}:", target).unwrap();
+                    }
+                }
+                Statement::Goto(target) => writeln!(output, "{}goto L{};", indent, target).unwrap(),
+                Statement::Break(None) => writeln!(output, "{}break;", indent).unwrap(),
+                Statement::Break(Some(l)) => writeln!(output, "{}break 'l{};", indent, l).unwrap(),
+                Statement::Continue(None) => writeln!(output, "{}continue;", indent).unwrap(),
+                Statement::Continue(Some(l)) => {
+                    writeln!(output, "{}continue 'l{};", indent, l).unwrap()
+                }
+                Statement::If(condition, body) => {
+                    writeln!(output, "{}if {} {{", indent, condition.render()).unwrap();
+                    self.render(body, depth + 1, output);
+                    writeln!(output, "{}}}", indent).unwrap();
+                }
+                Statement::Loop(start, body) => {
+                    writeln!(output, "{}{}loop {{", indent, label(start)).unwrap();
+                    self.render(body, depth + 1, output);
+                    writeln!(output, "{}}}", indent).unwrap();
+                }
+                Statement::While(start, condition, body) => {
+                    let condition = condition.render();
+                    writeln!(output, "{}{}while {} {{", indent, label(start), condition).unwrap();
+                    self.render(body, depth + 1, output);
+                    writeln!(output, "{}}}", indent).unwrap();
+                }
+            }
+        }
+    }
+}
+
+fn register(register: Register) -> String {
+    Operand::Register(register).to_string()
+}
+
+fn operand(operand: &Operand) -> String {
+    operand.to_string()
+}
+
+/// The straight-line code an instruction turns into
+fn line(instruction: Instruction) -> Vec<String> {
+    match instruction {
+        Instruction::Copy(x, y) => vec![format!("{} = {}", operand(&y), operand(&x))],
+        Instruction::Inc(x) => vec![format!("{} += 1", operand(&x))],
+        Instruction::Dec(x) => vec![format!("{} -= 1", operand(&x))],
+        Instruction::JumpNonZero(Operand::Raw(0), _) => vec![],
+        Instruction::JumpNonZero(Operand::Raw(_), y) => {
+            vec![format!("jump_relative({})", operand(&y))]
+        }
+        Instruction::JumpNonZero(x, y) => vec![format!(
+            "if {} != 0 {{ jump_relative({}) }}",
+            operand(&x),
+            operand(&y)
+        )],
+        Instruction::Toggle(x) => vec![format!("toggle_relative({})", operand(&x))],
+        Instruction::Out(x) => vec![format!("out({})", operand(&x))],
+        Instruction::Add(x, y) => vec![
+            format!("{} += {}", register(y), register(x)),
+            format!("{} = 0", register(x)),
+        ],
+        Instruction::Mul(x, y, z, w) => vec![
+            format!("{} += {} * {}", register(z), operand(&x), register(y)),
+            format!("{} = 0", register(y)),
+            format!("{} = 0", register(w)),
+        ],
+        Instruction::Nop => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::parse_program;
+
+    #[test]
+    fn test_decompile_arithmetic() {
+        let program = parse_program("cpy 3 b\ndec b\ninc a\njnz b -2").unwrap();
+
+        assert_eq!(decompile(&program), "b = 3;\na += b;\nb = 0;\n");
+    }
+
+    #[test]
+    fn test_decompile_goto() {
+        // jumps into the middle of a loop can't be nested
+        let program = parse_program("jnz a 3\ninc b\ninc c\njnz b -2").unwrap();
+
+        assert_eq!(
+            decompile(&program),
+            "\
+if a != 0 {
+    goto L3;
+}
+L1:
+b += 1;
+c += 1;
+L3:
+if b != 0 {
+    goto L1;
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_decompile_day25() {
+        let program = parse_program(include_str!("../../input/2016/day25.txt")).unwrap();
+
+        assert_eq!(
+            decompile(&program),
+            "\
+d = a;
+c = 14;
+d += 182 * c;
+c = 0;
+b = 0;
+loop {
+    a = d;
+    loop {
+        b = a;
+        a = 0;
+        'l12: loop {
+            c = 2;
+            loop {
+                if b == 0 {
+                    break 'l12;
+                }
+                b -= 1;
+                c -= 1;
+                if c == 0 {
+                    break;
+                }
+            }
+            a += 1;
+        }
+        b = 2;
+        while c != 0 {
+            b -= 1;
+            c -= 1;
+        }
+        out(b);
+        if a == 0 {
+            break;
+        }
+    }
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_decompile_jump_to_outer_condition() {
+        // the jump out of the inner loop goes to the outer loop's condition, which needs a label
+        let program =
+            parse_program("inc a\ninc b\njnz c 4\ndec b\njnz b -3\ndec a\njnz a -6\ninc d")
+                .unwrap();
+
+        assert_eq!(
+            decompile(&program),
+            "\
+loop {
+    a += 1;
+    loop {
+        b += 1;
+        if c != 0 {
+            goto L6;
+        }
+        b -= 1;
+        if b == 0 {
+            break;
+        }
+    }
+    a -= 1;
+L6:
+    if a == 0 {
+        break;
+    }
+}
+d += 1;
+"
+        );
+
+        // but when the outer loop always loops, it's just a continue
+        let program =
+            parse_program("inc a\ninc b\njnz c 4\ndec b\njnz b -3\ndec a\njnz 1 -6").unwrap();
+
+        assert_eq!(
+            decompile(&program),
+            "\
+'l0: loop {
+    a += 1;
+    loop {
+        b += 1;
+        if c != 0 {
+            continue 'l0;
+        }
+        b -= 1;
+        if b == 0 {
+            break;
+        }
+    }
+    a -= 1;
+}
+"
+        );
+    }
+}
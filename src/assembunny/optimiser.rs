@@ -0,0 +1,190 @@
+use super::{Instruction, Operand, Register};
+
+/// Rewrite the add and multiply loop idioms into synthetic `Add`/`Mul` instructions
+///
+/// The program keeps the same length so that jump offsets still line up, with the rest of each
+/// replaced loop filled with `Nop`s
+pub fn optimise(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut optimised = instructions.to_vec();
+    let mut i = 0;
+
+    while i < instructions.len() {
+        let window = &instructions[i..];
+
+        let replacement = match (multiply_loop(window), add_loop(window)) {
+            (Some(instruction), _) => Some((instruction, 6)),
+            (None, Some((x, y))) => Some((Instruction::Add(x, y), 3)),
+            (None, None) => None,
+        };
+
+        match replacement {
+            Some((instruction, length)) => {
+                optimised[i] = instruction;
+                optimised[i + 1..i + length]
+                    .iter_mut()
+                    .for_each(|slot| *slot = Instruction::Nop);
+                i += length;
+            }
+            None => i += 1,
+        }
+    }
+
+    optimised
+}
+
+/// Match a loop which adds register x to register y, e.g.
+///
+/// ```text
+/// inc y
+/// dec x
+/// jnz x -2
+/// ```
+///
+/// The `inc` and `dec` can come in either order. Returns `(x, y)` if matched.
+fn add_loop(window: &[Instruction]) -> Option<(Register, Register)> {
+    use Instruction::{Dec, Inc, JumpNonZero};
+    use Operand::{Raw, Register as R};
+
+    match window {
+        [Inc(R(y)), Dec(R(x)), JumpNonZero(R(j), Raw(-2)), ..]
+        | [Dec(R(x)), Inc(R(y)), JumpNonZero(R(j), Raw(-2)), ..]
+            if x != y && j == x =>
+        {
+            Some((*x, *y))
+        }
+        _ => None,
+    }
+}
+
+/// Match a nested loop which multiplies x by register y into register z, using w as scratch, e.g.
+///
+/// ```text
+/// cpy x w
+/// inc z
+/// dec w
+/// jnz w -2
+/// dec y
+/// jnz y -5
+/// ```
+fn multiply_loop(window: &[Instruction]) -> Option<Instruction> {
+    use Instruction::{Copy, Dec, JumpNonZero};
+    use Operand::{Raw, Register as R};
+
+    match window {
+        [Copy(x, R(w)), a, b, c, Dec(R(y)), JumpNonZero(R(j), Raw(-5)), ..] if j == y => {
+            let (inner, z) = add_loop(&[*a, *b, *c])?;
+
+            let distinct = inner == *w && y != w && z != *y && z != *w;
+            let factor_unchanged = match x {
+                R(x) => x != w && x != y && *x != z,
+                Raw(_) => true,
+            };
+
+            if distinct && factor_unchanged {
+                Some(Instruction::Mul(*x, *y, z, *w))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembunny::{parse_program, Computer};
+
+    #[test]
+    fn test_optimise_add() {
+        let program = parse_program("cpy 3 b\ndec b\ninc a\njnz b -2\ninc a").unwrap();
+        let optimised = optimise(&program);
+
+        assert_eq!(
+            optimised[1..],
+            [
+                Instruction::Add(1, 0),
+                Instruction::Nop,
+                Instruction::Nop,
+                Instruction::Inc(Operand::Register(0))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optimise_multiply() {
+        let program = parse_program("cpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5").unwrap();
+        let optimised = optimise(&program);
+
+        assert_eq!(
+            optimised[0],
+            Instruction::Mul(Operand::Register(1), 3, 0, 2)
+        );
+        assert!(optimised[1..].iter().all(|i| *i == Instruction::Nop));
+    }
+
+    #[test]
+    fn test_optimise_ignores_overlapping_registers() {
+        // the factor is changed by the outer loop so this isn't a straight multiply
+        let program = parse_program("cpy d c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5").unwrap();
+        let optimised = optimise(&program);
+
+        assert_eq!(optimised[0], program[0]);
+        assert_eq!(optimised[1], Instruction::Add(2, 0));
+    }
+
+    #[test]
+    fn test_execute_optimised() {
+        let source = "cpy 6 b\ncpy 7 d\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5";
+
+        let mut computer = Computer::new();
+        computer
+            .execute(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        let mut optimised = Computer::new();
+        optimised
+            .execute_optimised(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        assert_eq!(optimised.registers, computer.registers);
+        assert_eq!(optimised.registers[0], 42);
+    }
+
+    #[test]
+    fn test_execute_optimised_jump_into_loop() {
+        // jumps straight to the `dec c` in the middle of the add loop
+        let source = "cpy 3 c\njnz 1 2\ninc a\ndec c\njnz c -2";
+
+        let mut computer = Computer::new();
+        computer
+            .execute(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        let mut optimised = Computer::new();
+        optimised
+            .execute_optimised(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        assert_eq!(optimised.registers, computer.registers);
+        assert_eq!(optimised.registers[0], 2);
+    }
+
+    #[test]
+    fn test_execute_optimised_toggle() {
+        // the `tgl` turns the infinite `inc`/`inc` loop into an add loop
+        let source = "cpy 5 d\ntgl 2\ninc a\ninc d\njnz d -2";
+
+        let mut computer = Computer::new();
+        computer
+            .execute(&mut parse_program(source).unwrap())
+            .unwrap();
+
+        let mut program = parse_program(source).unwrap();
+        let mut optimised = Computer::new();
+        optimised.execute_optimised(&mut program).unwrap();
+
+        assert_eq!(optimised.registers, computer.registers);
+        assert_eq!(program[3], Instruction::Dec(Operand::Register(3)));
+    }
+}
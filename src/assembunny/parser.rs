@@ -0,0 +1,225 @@
+use super::{Config, Instruction, Operand, Value};
+use std::{error::Error, fmt};
+
+/// Every instruction form the parser accepts, where `r` must be a register and `x` can be a
+/// register or an integer
+const FORMS: &[&str] = &["cpy x r", "inc r", "dec r", "jnz x x", "tgl x", "out x"];
+
+pub(super) const REGISTER: &str = "a register";
+pub(super) const INTEGER: &str = "an integer";
+pub(super) const LABEL: &str = "a label";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number in the source, starting from 1
+    pub line: usize,
+
+    /// Column of the offending token, starting from 1
+    pub column: usize,
+
+    /// The offending token, or empty if the line ended too soon
+    pub token: String,
+
+    /// Descriptions of what would have been accepted instead
+    pub expected: Vec<&'static str>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+
+        if self.token.is_empty() {
+            write!(f, "unexpected end of line")?;
+        } else {
+            write!(f, "unexpected `{}`", self.token)?;
+        }
+
+        write!(f, ", expected {}", self.expected.join(" or "))
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse an assembunny program, one instruction per line
+///
+/// Blank lines and `//` comments are ignored
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_program_with(input, &Config::default())
+}
+
+/// Parse an assembunny program using the register names from the given config
+pub fn parse_program_with(input: &str, config: &Config) -> Result<Vec<Instruction>, ParseError> {
+    let mut instructions = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let code = strip_comment(line);
+        let tokens = tokenise(code);
+
+        if !tokens.is_empty() {
+            instructions.push(parse_instruction(index + 1, code, &tokens, config, None)?);
+        }
+    }
+
+    Ok(instructions)
+}
+
+pub(super) fn strip_comment(line: &str) -> &str {
+    line.split("//").next().unwrap()
+}
+
+/// Split a line on whitespace, keeping the column each token starts at
+pub(super) fn tokenise(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push((s + 1, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Looks up a label, returning the relative offset to it from the current instruction
+pub(super) type Resolver<'a> = &'a dyn Fn(&str) -> Option<Value>;
+
+/// Parse a single tokenised instruction
+///
+/// If given, operands which are relative offsets (the target of a `jnz` or `tgl`) are passed to
+/// `resolve` if they aren't a register or integer, so that the assembler can fill in labels.
+pub(super) fn parse_instruction(
+    line: usize,
+    code: &str,
+    tokens: &[(usize, &str)],
+    config: &Config,
+    resolve: Option<Resolver>,
+) -> Result<Instruction, ParseError> {
+    let error = |(column, token): (usize, &str), expected: Vec<&'static str>| ParseError {
+        line,
+        column,
+        token: token.to_string(),
+        expected,
+    };
+
+    let mnemonic = tokens[0];
+    let operands = &tokens[1..];
+
+    let form = FORMS
+        .iter()
+        .find(|form| form.split(' ').next() == Some(mnemonic.1))
+        .ok_or_else(|| error(mnemonic, FORMS.to_vec()))?;
+    let arity = form.split(' ').count() - 1;
+
+    if operands.len() != arity {
+        // point at the first surplus token, or the end of the line if some are missing
+        let token = operands
+            .get(arity)
+            .copied()
+            .unwrap_or((code.trim_end().len() + 1, ""));
+
+        return Err(error(token, vec![form]));
+    }
+
+    let register = |token: (usize, &str)| match config.operand(token.1) {
+        Some(operand @ Operand::Register(_)) => Ok(operand),
+        _ => Err(error(token, vec![REGISTER])),
+    };
+
+    let value = |token: (usize, &str)| {
+        config
+            .operand(token.1)
+            .ok_or_else(|| error(token, vec![REGISTER, INTEGER]))
+    };
+
+    let offset = |token: (usize, &str)| match (config.operand(token.1), resolve) {
+        (Some(operand), _) => Ok(operand),
+        (None, None) => Err(error(token, vec![REGISTER, INTEGER])),
+        (None, Some(resolve)) => resolve(token.1)
+            .map(Operand::Raw)
+            .ok_or_else(|| error(token, vec![REGISTER, INTEGER, LABEL])),
+    };
+
+    Ok(match mnemonic.1 {
+        "cpy" => Instruction::Copy(value(operands[0])?, register(operands[1])?),
+        "inc" => Instruction::Inc(register(operands[0])?),
+        "dec" => Instruction::Dec(register(operands[0])?),
+        "jnz" => Instruction::JumpNonZero(value(operands[0])?, offset(operands[1])?),
+        "tgl" => Instruction::Toggle(offset(operands[0])?),
+        "out" => Instruction::Out(value(operands[0])?),
+        _ => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_program() {
+        let program = parse_program("cpy 41 a  // comment\n\n  jnz a -2\n").unwrap();
+
+        assert_eq!(
+            program,
+            vec![
+                Instruction::Copy(Operand::Raw(41), Operand::Register(0)),
+                Instruction::JumpNonZero(Operand::Register(0), Operand::Raw(-2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_instruction() {
+        let error = parse_program("inc a\n  mul a b").unwrap_err();
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 3);
+        assert_eq!(error.token, "mul");
+        assert_eq!(error.expected, FORMS);
+    }
+
+    #[test]
+    fn test_invalid_operand() {
+        let error = parse_program("cpy 1 2").unwrap_err();
+
+        assert_eq!(
+            error,
+            ParseError {
+                line: 1,
+                column: 7,
+                token: "2".to_string(),
+                expected: vec![REGISTER],
+            }
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 7: unexpected `2`, expected a register"
+        );
+
+        let error = parse_program("jnz e 2").unwrap_err();
+        assert_eq!(error.column, 5);
+        assert_eq!(error.expected, vec![REGISTER, INTEGER]);
+    }
+
+    #[test]
+    fn test_wrong_operand_count() {
+        let error = parse_program("jnz a").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 6: unexpected end of line, expected jnz x x"
+        );
+
+        let error = parse_program("inc a b").unwrap_err();
+        assert_eq!(error.column, 7);
+        assert_eq!(error.token, "b");
+    }
+}
@@ -0,0 +1,170 @@
+//! Runs the solutions without needing `cargo aoc`
+//!
+//! ```text
+//! advent <day> [part] [input]
+//! advent --all [input directory]
+//! ```
+//!
+//! The input is a file path, or `-` to read from stdin, and defaults to `input/2016/day<day>.txt`.
+//! Both parts are run if the part is left out.
+
+use advent_2016::{
+    runner::{self, Answer, Solution},
+    Part,
+};
+use std::{
+    any::Any,
+    env,
+    error::Error,
+    fs,
+    io::{self, Read},
+    panic,
+    path::Path,
+    process,
+    time::{Duration, Instant},
+};
+
+const USAGE: &str = "usage: advent <day> [part] [input | -]\n       advent --all [input directory]";
+const INPUT_DIRECTORY: &str = "input/2016";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let result = match args[..] {
+        ["--all"] => run_all(Path::new(INPUT_DIRECTORY)),
+        ["--all", directory] => run_all(Path::new(directory)),
+        ["-h"] | ["--help"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        [day] => run_day(day, None, None),
+        [day, part] => run_day(day, Some(part), None),
+        [day, part, input] => run_day(day, Some(part), Some(input)),
+        _ => Err(USAGE.into()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// Run one or both parts of a day, printing the answers as they're found
+fn run_day(day: &str, part: Option<&str>, input: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let day = day
+        .parse()
+        .map_err(|_| format!("invalid day '{}'\n{}", day, USAGE))?;
+
+    let solutions: Vec<&Solution> = match part {
+        None => runner::SOLUTIONS.iter().filter(|s| s.day == day).collect(),
+        Some("1") => runner::find(day, Part::One).into_iter().collect(),
+        Some("2") => runner::find(day, Part::Two).into_iter().collect(),
+        Some(part) => return Err(format!("invalid part '{}'\n{}", part, USAGE).into()),
+    };
+
+    if solutions.is_empty() {
+        return Err(format!(
+            "no solution for day {} part {}",
+            day,
+            part.unwrap_or("1 or 2")
+        )
+        .into());
+    }
+
+    let input = match input {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path, e))?
+        }
+        None => read_input(Path::new(INPUT_DIRECTORY), day)?,
+    };
+
+    for solution in solutions {
+        let (answer, elapsed) = time(solution, &input);
+
+        println!(
+            "Day {} part {}: {} ({:.2?})",
+            day, solution.part, answer?, elapsed
+        );
+    }
+
+    Ok(())
+}
+
+/// Run every solution and print a summary table, carrying on past any which fail
+fn run_all(directory: &Path) -> Result<(), Box<dyn Error>> {
+    let mut total = Duration::default();
+    let mut failures = 0;
+
+    println!("{:>3}  {:>4}  {:>10}  Answer", "Day", "Part", "Time");
+
+    for solution in runner::SOLUTIONS {
+        let (answer, elapsed) = match read_input(directory, solution.day) {
+            Ok(input) => time(solution, &input),
+            Err(e) => (Err(e), Duration::default()),
+        };
+
+        let answer = answer.unwrap_or_else(|e| {
+            failures += 1;
+            format!("error: {}", e)
+        });
+
+        total += elapsed;
+        println!(
+            "{:>3}  {:>4}  {:>10}  {}",
+            solution.day,
+            solution.part,
+            format!("{:.2?}", elapsed),
+            answer
+        );
+    }
+
+    println!("\n{} solutions in {:.2?}", runner::SOLUTIONS.len(), total);
+
+    match failures {
+        0 => Ok(()),
+        n => Err(format!("{} solution(s) failed", n).into()),
+    }
+}
+
+fn read_input(directory: &Path, day: u8) -> Result<String, Box<dyn Error>> {
+    let path = directory.join(format!("day{}.txt", day));
+
+    fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e).into())
+}
+
+/// Solve the part, including parsing the input, and time how long it takes
+///
+/// Some solutions still panic on input they don't expect, so that's caught and treated like any
+/// other error
+fn time(solution: &Solution, input: &str) -> (Answer, Duration) {
+    // the panic is reported as the answer, so silence the default message while solving
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let start = Instant::now();
+    let answer = panic::catch_unwind(|| (solution.solve)(input))
+        .unwrap_or_else(|payload| Err(panic_message(payload).into()));
+    let elapsed = start.elapsed();
+
+    panic::set_hook(hook);
+
+    (answer, elapsed)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => "unknown error".to_string(),
+        },
+    };
+
+    format!("panicked: {}", message)
+}
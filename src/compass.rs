@@ -1,105 +1,483 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+};
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Turn {
+    /// 90° anticlockwise
     Left,
+
+    /// 90° clockwise
     Right,
+
+    /// 180°
+    Around,
+
+    /// 45° anticlockwise
+    SlightLeft,
+
+    /// 45° clockwise
+    SlightRight,
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+impl Turn {
+    /// The turn clockwise in eighths of a full turn
+    fn eighths(&self) -> usize {
+        match self {
+            Turn::SlightRight => 1,
+            Turn::Right => 2,
+            Turn::Around => 4,
+            Turn::Left => 6,
+            Turn::SlightLeft => 7,
+        }
+    }
+}
+
+/// Which way the y axis points, so that north can be up on screen whichever way a grid is stored
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Convention {
+    /// Cartesian coordinates, where north increases y
+    YUp,
+
+    /// Screen coordinates, where (0, 0) is the top left and north decreases y
+    YDown,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum Direction {
     North,
-    South,
+    NorthEast,
     East,
+    SouthEast,
+    South,
+    SouthWest,
     West,
+    NorthWest,
 }
 
 impl Direction {
-    /// From a given direction, turn left or right to a new direction
+    /// All 8 directions, clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The 4 cardinal directions, clockwise from north
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// From a given direction, turn to a new direction
     pub fn turn(&self, turn: &Turn) -> Self {
-        match turn {
-            Turn::Left => match self {
-                Direction::North => Direction::West,
-                Direction::South => Direction::East,
-                Direction::East => Direction::North,
-                Direction::West => Direction::South,
-            },
-            Turn::Right => match self {
-                Direction::North => Direction::East,
-                Direction::South => Direction::West,
-                Direction::East => Direction::South,
-                Direction::West => Direction::North,
-            },
+        let index = Direction::ALL.iter().position(|d| d == self).unwrap();
+        Direction::ALL[(index + turn.eighths()) % 8]
+    }
+
+    /// The change in x and y from moving a step in this direction
+    pub fn offset(&self, convention: Convention) -> (i32, i32) {
+        let (x, y) = match self {
+            Direction::North => (0, 1),
+            Direction::NorthEast => (1, 1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, -1),
+            Direction::South => (0, -1),
+            Direction::SouthWest => (-1, -1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, 1),
+        };
+
+        match convention {
+            Convention::YUp => (x, y),
+            Convention::YDown => (x, -y),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Copy, Clone)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseDirectionError(pub String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown direction `{}`", self.0)
+    }
 }
 
-impl Default for Point {
-    fn default() -> Self {
-        Point::new(0, 0)
+impl Error for ParseDirectionError {}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    /// Parse a compass point (`N`, `NE`...), a screen direction (`U`, `D`, `L`, `R`) or an arrow
+    /// (`↑`, `↗`... or `^`, `v`, `<`, `>`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "N" | "U" | "↑" | "^" => Direction::North,
+            "NE" | "↗" => Direction::NorthEast,
+            "E" | "R" | "→" | ">" => Direction::East,
+            "SE" | "↘" => Direction::SouthEast,
+            "S" | "D" | "↓" | "v" => Direction::South,
+            "SW" | "↙" => Direction::SouthWest,
+            "W" | "L" | "←" | "<" => Direction::West,
+            "NW" | "↖" => Direction::NorthWest,
+            _ => return Err(ParseDirectionError(s.to_string())),
+        })
     }
 }
 
-impl Point {
+/// Directions between the cells of a hex grid with flat-topped hexagons
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    /// All 6 directions, clockwise from north
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::North,
+        HexDirection::NorthEast,
+        HexDirection::SouthEast,
+        HexDirection::South,
+        HexDirection::SouthWest,
+        HexDirection::NorthWest,
+    ];
+
+    /// Turn by 60° clockwise, or anticlockwise if negative, for each sixth
+    pub fn turn(&self, sixths: i32) -> Self {
+        let index = HexDirection::ALL.iter().position(|d| d == self).unwrap() as i32;
+        HexDirection::ALL[(index + sixths).rem_euclid(6) as usize]
+    }
+
+    /// The change in cube coordinates from moving a step in this direction, where the
+    /// coordinates of every cell add up to zero
+    pub fn offset(&self) -> Point3 {
+        match self {
+            HexDirection::North => Point3::new(0, 1, -1),
+            HexDirection::NorthEast => Point3::new(1, 0, -1),
+            HexDirection::SouthEast => Point3::new(1, -1, 0),
+            HexDirection::South => Point3::new(0, -1, 1),
+            HexDirection::SouthWest => Point3::new(-1, 0, 1),
+            HexDirection::NorthWest => Point3::new(-1, 1, 0),
+        }
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = ParseDirectionError;
+
+    /// Parse `n`, `ne`, `se`, `s`, `sw` or `nw`, in either case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "n" => HexDirection::North,
+            "ne" => HexDirection::NorthEast,
+            "se" => HexDirection::SouthEast,
+            "s" => HexDirection::South,
+            "sw" => HexDirection::SouthWest,
+            "nw" => HexDirection::NorthWest,
+            _ => return Err(ParseDirectionError(s.to_string())),
+        })
+    }
+}
+
+/// A numeric type which can be used for the coordinates of a point
+pub trait Coordinate:
+    Copy + Default + Ord + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ONE: Self;
+
+    /// The absolute difference between two values, which works for unsigned types too
+    fn distance(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(impl Coordinate for $t {
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A 2D point, with `i32` coordinates unless specified
+///
+/// Moving or finding neighbours below zero overflows with unsigned coordinates
+#[derive(Debug, Default, PartialEq, Ord, PartialOrd, Eq, Hash, Copy, Clone)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
     /// Create a new point
-    pub fn new(x: i32, y: i32) -> Self {
+    pub const fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
+}
 
-    /// Move one step in the given direction
-    pub fn move_direction(&self, direction: &Direction) -> Point {
-        self.move_direction_steps(direction, 1)
-    }
-
-    /// Move one or more steps in the given direction
-    pub fn move_direction_steps(&self, direction: &Direction, steps: i32) -> Point {
-        match direction {
-            Direction::North => Point {
-                x: self.x,
-                y: self.y + steps,
-            },
-            Direction::South => Point {
-                x: self.x,
-                y: self.y - steps,
-            },
-            Direction::East => Point {
-                x: self.x + steps,
-                y: self.y,
-            },
-            Direction::West => Point {
-                x: self.x - steps,
-                y: self.y,
-            },
-        }
+impl<T: Coordinate> Point<T> {
+    /// Move one step in the given direction, where north increases y
+    pub fn move_direction(&self, direction: &Direction) -> Self {
+        self.move_direction_steps(direction, T::ONE)
+    }
+
+    /// Move one or more steps in the given direction, where north increases y
+    pub fn move_direction_steps(&self, direction: &Direction, steps: T) -> Self {
+        self.steps(direction, steps, Convention::YUp)
+    }
+
+    /// Move one step in the given direction with the given y axis convention
+    pub fn step(&self, direction: &Direction, convention: Convention) -> Self {
+        self.steps(direction, T::ONE, convention)
+    }
+
+    /// Move one or more steps in the given direction with the given y axis convention
+    pub fn steps(&self, direction: &Direction, steps: T, convention: Convention) -> Self {
+        let shift = |value: T, offset: i32| match offset {
+            1 => value + steps,
+            -1 => value - steps,
+            _ => value,
+        };
+
+        let (x, y) = direction.offset(convention);
+        Point::new(shift(self.x, x), shift(self.y, y))
     }
 
     /// Get the 4 horizontal and vertical neighbours of a point
-    pub fn neighbours_4(&self) -> [Point; 4] {
+    pub fn neighbours_4(&self) -> [Self; 4] {
+        let one = T::ONE;
+
         [
-            Point::new(self.x, self.y - 1),
-            Point::new(self.x - 1, self.y),
-            Point::new(self.x + 1, self.y),
-            Point::new(self.x, self.y + 1),
+            Point::new(self.x, self.y - one),
+            Point::new(self.x - one, self.y),
+            Point::new(self.x + one, self.y),
+            Point::new(self.x, self.y + one),
         ]
     }
 
     /// Get the 8 horizontal, vertical and diagonal neighbours of a point
-    pub fn _neighbours_8(&self) -> [Point; 8] {
+    pub fn neighbours_8(&self) -> [Self; 8] {
+        let one = T::ONE;
+
+        [
+            Point::new(self.x - one, self.y - one),
+            Point::new(self.x, self.y - one),
+            Point::new(self.x + one, self.y - one),
+            Point::new(self.x - one, self.y),
+            Point::new(self.x + one, self.y),
+            Point::new(self.x - one, self.y + one),
+            Point::new(self.x, self.y + one),
+            Point::new(self.x + one, self.y + one),
+        ]
+    }
+
+    /// Distance moving only horizontally and vertically
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// Distance moving diagonally as well, i.e. the number of king moves in chess
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// Square of the straight line distance, which avoids needing a square root
+    pub fn euclidean_sq(&self, other: &Self) -> T {
+        let (x, y) = (self.x.distance(other.x), self.y.distance(other.y));
+        x * x + y * y
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Point::new(self.x * scale, self.y * scale)
+    }
+}
+
+/// A 3D point, with `i32` coordinates unless specified
+#[derive(Debug, Default, PartialEq, Ord, PartialOrd, Eq, Hash, Copy, Clone)]
+pub struct Point3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    /// Create a new point
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Coordinate> Point3<T> {
+    /// Get the 6 neighbours of a point which share a face with it
+    pub fn neighbours_6(&self) -> [Self; 6] {
+        let one = T::ONE;
+
         [
-            Point::new(self.x - 1, self.y - 1),
-            Point::new(self.x, self.y - 1),
-            Point::new(self.x + 1, self.y - 1),
-            Point::new(self.x - 1, self.y),
-            Point::new(self.x + 1, self.y),
-            Point::new(self.x - 1, self.y + 1),
-            Point::new(self.x, self.y + 1),
-            Point::new(self.x + 1, self.y + 1),
+            Point3::new(self.x - one, self.y, self.z),
+            Point3::new(self.x + one, self.y, self.z),
+            Point3::new(self.x, self.y - one, self.z),
+            Point3::new(self.x, self.y + one, self.z),
+            Point3::new(self.x, self.y, self.z - one),
+            Point3::new(self.x, self.y, self.z + one),
         ]
     }
+
+    /// Distance moving only along the axes
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+
+    /// Distance moving diagonally as well
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x
+            .distance(other.x)
+            .max(self.y.distance(other.y))
+            .max(self.z.distance(other.z))
+    }
+
+    /// Square of the straight line distance, which avoids needing a square root
+    pub fn euclidean_sq(&self, other: &Self) -> T {
+        let (x, y, z) = (
+            self.x.distance(other.x),
+            self.y.distance(other.y),
+            self.z.distance(other.z),
+        );
+        x * x + y * y + z * z
+    }
+}
+
+impl Point3 {
+    /// Move one step in the given direction on a hex grid, see [`HexDirection::offset`]
+    pub fn move_hex(&self, direction: &HexDirection) -> Self {
+        *self + direction.offset()
+    }
+
+    /// The number of steps between two cells of a hex grid
+    pub fn hex_distance(&self, other: &Self) -> i32 {
+        self.manhattan(other) / 2
+    }
+}
+
+impl<T: Coordinate> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Coordinate> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+/// An axis-aligned rectangle, including the points on its edges
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Rect<T = i32> {
+    /// The corner with the smallest coordinates
+    pub min: Point<T>,
+
+    /// The corner with the largest coordinates
+    pub max: Point<T>,
+}
+
+impl<T> Rect<T> {
+    pub const fn new(min: Point<T>, max: Point<T>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl<T: Coordinate> Rect<T> {
+    /// The smallest rectangle containing all of the points, or None if there aren't any
+    pub fn bounding(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        points.into_iter().fold(None, |rect, point| {
+            Some(match rect {
+                None => Rect::new(point, point),
+                Some(Rect { min, max }) => Rect::new(
+                    Point::new(min.x.min(point.x), min.y.min(point.y)),
+                    Point::new(max.x.max(point.x), max.y.max(point.y)),
+                ),
+            })
+        })
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    /// Check whether the point is inside the rectangle or on its edge
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Every point in the rectangle, a row at a time
+    pub fn points(&self) -> impl Iterator<Item = Point<T>> {
+        let Rect { min, max } = *self;
+        // stop before stepping past the end, so ranges ending at T::MAX don't overflow
+        let range = |from: T, to: T| {
+            let next = move |&i: &T| if i < to { Some(i + T::ONE) } else { None };
+            std::iter::successors(Some(from).filter(|&from| from <= to), next)
+        };
+
+        range(min.y, max.y).flat_map(move |y| range(min.x, max.x).map(move |x| Point::new(x, y)))
+    }
 }
 
 #[cfg(test)]
@@ -121,16 +499,95 @@ mod tests {
         assert_eq!(Direction::West.turn(&Turn::Right), Direction::North);
     }
 
+    #[test]
+    fn test_turn_8() {
+        assert_eq!(Direction::North.turn(&Turn::Around), Direction::South);
+        assert_eq!(Direction::East.turn(&Turn::Around), Direction::West);
+        assert_eq!(
+            Direction::North.turn(&Turn::SlightRight),
+            Direction::NorthEast
+        );
+        assert_eq!(
+            Direction::North.turn(&Turn::SlightLeft),
+            Direction::NorthWest
+        );
+        assert_eq!(
+            Direction::SouthWest.turn(&Turn::Right),
+            Direction::NorthWest
+        );
+
+        // 8 slight turns go all the way round
+        let mut direction = Direction::East;
+        for _ in 0..8 {
+            direction = direction.turn(&Turn::SlightLeft);
+        }
+        assert_eq!(direction, Direction::East);
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        for s in &["N", "U", "↑", "^"] {
+            assert_eq!(s.parse(), Ok(Direction::North));
+        }
+
+        assert_eq!("R".parse(), Ok(Direction::East));
+        assert_eq!("v".parse(), Ok(Direction::South));
+        assert_eq!("←".parse(), Ok(Direction::West));
+        assert_eq!("SE".parse(), Ok(Direction::SouthEast));
+        assert_eq!("↖".parse(), Ok(Direction::NorthWest));
+        assert_eq!(
+            "X".parse::<Direction>(),
+            Err(ParseDirectionError("X".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_convention() {
+        let origin = Point::new(0, 0);
+
+        assert_eq!(
+            origin.step(&Direction::North, Convention::YUp),
+            Point::new(0, 1)
+        );
+        assert_eq!(
+            origin.step(&Direction::North, Convention::YDown),
+            Point::new(0, -1)
+        );
+        assert_eq!(
+            origin.steps(&Direction::SouthEast, 3, Convention::YDown),
+            Point::new(3, 3)
+        );
+
+        // every neighbour is one step away in some direction
+        for direction in Direction::ALL.iter() {
+            let next = origin.step(direction, Convention::YDown);
+            assert!(origin.neighbours_8().contains(&next));
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        let path = "ne,ne,s,s"
+            .split(',')
+            .map(|s| s.parse::<HexDirection>().unwrap());
+        let end = path.fold(Point3::new(0, 0, 0), |p, d| p.move_hex(&d));
+
+        assert_eq!(end, Point3::new(2, -2, 0));
+        assert_eq!(end.hex_distance(&Point3::new(0, 0, 0)), 2);
+        assert_eq!(HexDirection::North.turn(-1), HexDirection::NorthWest);
+        assert_eq!(HexDirection::South.turn(3), HexDirection::North);
+    }
+
     #[test]
     fn test_move_direction() {
         // back to original position
         assert_eq!(
-            Point::default()
+            Point::new(0, 0)
                 .move_direction(&Direction::North)
                 .move_direction(&Direction::South)
                 .move_direction(&Direction::East)
                 .move_direction(&Direction::West),
-            Point::default()
+            Point::new(0, 0)
         );
     }
 
@@ -138,12 +595,93 @@ mod tests {
     fn test_move_direction_steps() {
         // back to original position
         assert_eq!(
-            Point::default()
+            Point::new(0, 0)
                 .move_direction_steps(&Direction::North, 2)
                 .move_direction_steps(&Direction::South, 2)
                 .move_direction_steps(&Direction::East, 2)
                 .move_direction_steps(&Direction::West, 2),
-            Point::default()
+            Point::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+
+        assert_eq!(a + b, Point::new(-2, 7));
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(b * 2, Point::new(-6, 10));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1) * 2,
+            Point3::new(3, 4, 5)
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean_sq(&b), 25);
+
+        // unsigned coordinates don't underflow
+        let c: Point<usize> = Point::new(4, 1);
+        assert_eq!(c.manhattan(&Point::new(1, 3)), 5);
+
+        let d = Point3::new(1, 2, 3);
+        let e = Point3::new(4, 0, 3);
+        assert_eq!(d.manhattan(&e), 5);
+        assert_eq!(d.chebyshev(&e), 3);
+        assert_eq!(d.euclidean_sq(&e), 13);
+        assert_eq!(
+            d.neighbours_6()
+                .iter()
+                .filter(|n| n.manhattan(&d) == 1)
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_rect() {
+        let points = vec![Point::new(2, -1), Point::new(0, 3), Point::new(1, 1)];
+        let rect = Rect::bounding(points).unwrap();
+
+        assert_eq!(rect, Rect::new(Point::new(0, -1), Point::new(2, 3)));
+        assert_eq!((rect.width(), rect.height()), (3, 5));
+        assert!(rect.contains(&Point::new(2, 3)));
+        assert!(!rect.contains(&Point::new(3, 3)));
+
+        let points = rect.points().collect::<Vec<_>>();
+        assert_eq!(points.len(), 15);
+        assert_eq!(
+            points[..4],
+            [
+                Point::new(0, -1),
+                Point::new(1, -1),
+                Point::new(2, -1),
+                Point::new(0, 0)
+            ]
+        );
+
+        assert_eq!(Rect::<i32>::bounding(vec![]), None);
+
+        // ranges which end at the biggest value don't overflow
+        let rect = Rect::new(Point::new(254_u8, 255), Point::new(255, 255));
+        assert_eq!(
+            rect.points().collect::<Vec<_>>(),
+            [Point::new(254, 255), Point::new(255, 255)]
         );
     }
+
+    #[test]
+    fn test_coordinate_ord() {
+        // with Coordinate in scope these are still the usual Ord methods rather than ambiguous
+        let values = [3_u8, 7, 4];
+        assert_eq!(values.iter().fold(0, |a, &b| a.max(b)), 7);
+        assert_eq!(values.iter().fold(u8::MAX, |a, &b| a.min(b)), 3);
+    }
 }
@@ -28,7 +28,7 @@ pub fn generator(input: &str) -> Vec<Instruction> {
 
 #[aoc(day1, part1)]
 pub fn part1(input: &[Instruction]) -> i32 {
-    let mut current = Point::default();
+    let mut current: Point = Point::default();
     let mut direction = Direction::North;
 
     for instruction in input.iter() {
@@ -36,12 +36,12 @@ pub fn part1(input: &[Instruction]) -> i32 {
         current = current.move_direction_steps(&direction, instruction.steps);
     }
 
-    current.x.abs() + current.y.abs()
+    current.manhattan(&Point::default())
 }
 
 #[aoc(day1, part2)]
 pub fn part2(input: &[Instruction]) -> i32 {
-    let mut current = Point::default();
+    let mut current: Point = Point::default();
     let mut direction = Direction::North;
     let mut seen = HashSet::new();
 
@@ -52,7 +52,7 @@ pub fn part2(input: &[Instruction]) -> i32 {
             current = current.move_direction(&direction);
 
             if !seen.insert(current) {
-                return current.x.abs() + current.y.abs();
+                return current.manhattan(&Point::default());
             }
         }
     }
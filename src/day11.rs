@@ -1,49 +1,128 @@
+use crate::search::{self, Graph};
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{BTreeSet, BinaryHeap};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Write},
+};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+/// A generator or microchip, identified by the index of its element
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Item {
-    Generator(String),
-    Microchip(String),
+    Generator(usize),
+    Microchip(usize),
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+/// A single trip of the elevator, carrying at least one item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction,
+    pub items: Vec<Item>,
+}
+
+/// A safety rule which every state has to follow
+///
+/// Rules mustn't depend on which element is which, only on where the items are, because the
+/// search treats states which only differ by swapping elements around as the same.
+pub type Rule = fn(&State) -> bool;
+
+/// How the facility works
+#[derive(Debug, Clone)]
+pub struct Model {
+    pub floors: usize,
+
+    /// The most items the elevator can carry, it always needs at least one to move
+    pub capacity: usize,
+
+    pub rules: Vec<Rule>,
+}
+
+impl Default for Model {
+    /// The puzzle's facility with 4 floors and an elevator which carries 1 or 2 items
+    fn default() -> Self {
+        Model {
+            floors: 4,
+            capacity: 2,
+            rules: vec![no_fried_microchips],
+        }
+    }
+}
+
+impl Model {
+    /// Bits used for each floor number when packing a state, see [`State::canonical`]
+    fn floor_bits(&self) -> usize {
+        let top = self.floors.saturating_sub(1).max(1);
+        (usize::BITS - top.leading_zeros()) as usize
+    }
+}
+
+/// a state is valid if no microchip is on a floor with another generator but not its own
+pub fn no_fried_microchips(state: &State) -> bool {
+    state.pairs.iter().all(|&(generator, microchip)| {
+        generator == microchip || state.pairs.iter().all(|&(other, _)| other != microchip)
+    })
+}
+
+/// The puzzle input, i.e. the names of the elements and where everything starts
+#[derive(Debug, Clone)]
+pub struct Facility {
+    elements: Vec<String>,
+    start: State,
+    pub model: Model,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct State {
     elevator: usize,
-    floors: [BTreeSet<Item>; 4],
+
+    /// The floor of each element's generator and microchip
+    pairs: Vec<(usize, usize)>,
 }
 
 impl State {
-    /// generate all the potential valid next states
-    fn valid_moves(&self) -> Vec<Self> {
-        let mut moves = Vec::new();
-
-        let current_floor = &self.floors[self.elevator];
-        let directions = &[Direction::Up, Direction::Down];
+    pub fn elevator(&self) -> usize {
+        self.elevator
+    }
 
-        for direction in directions.iter() {
-            if self.next_floor(direction).is_none() {
-                continue;
-            }
+    /// The floor of each element's generator and microchip
+    pub fn pairs(&self) -> &[(usize, usize)] {
+        &self.pairs
+    }
 
-            for (i, one) in current_floor.iter().enumerate() {
-                // try taking 1 item with you
-                if let Some(next_state) = self.try_move(direction, &[one]) {
-                    moves.push(next_state);
-                }
+    /// generate all the potential valid moves and the states they lead to
+    fn valid_moves(&self, model: &Model) -> Vec<(Move, Self)> {
+        let mut moves = Vec::new();
 
-                // try taking 2 items with you
-                for two in current_floor.iter().skip(i + 1) {
-                    if let Some(next_state) = self.try_move(direction, &[one, two]) {
-                        moves.push(next_state);
+        let items = self
+            .pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(element, &(generator, microchip))| {
+                let generator =
+                    Some(Item::Generator(element)).filter(|_| generator == self.elevator);
+                let microchip =
+                    Some(Item::Microchip(element)).filter(|_| microchip == self.elevator);
+                generator.into_iter().chain(microchip)
+            })
+            .collect::<Vec<_>>();
+
+        for &direction in &[Direction::Up, Direction::Down] {
+            // try taking every combination of items that fits in the elevator
+            for count in 1..=model.capacity {
+                for items in items.iter().copied().combinations(count) {
+                    let next = Move { direction, items };
+
+                    if let Some(next_state) = self.try_move(&next, model) {
+                        moves.push((next, next_state));
                     }
                 }
             }
@@ -52,196 +131,378 @@ impl State {
         moves
     }
 
-    /// Try and move in the given direction
-    ///
-    /// Returns:
-    /// - None if the move is invalid
-    /// - Some(floor) containing the next floor if the move is valid
-    fn next_floor(&self, direction: &Direction) -> Option<usize> {
-        match (*direction, self.elevator) {
-            (Direction::Up, 0) => Some(1),
-            (Direction::Up, 1) => Some(2),
-            (Direction::Up, 2) => Some(3),
-            (Direction::Down, 1) => Some(0),
-            (Direction::Down, 2) => Some(1),
-            (Direction::Down, 3) => Some(2),
-            _ => None,
-        }
-    }
-
-    /// Try and move in the given direction and take the given items
+    /// Try and make the given move
     ///
     /// Returns:
     /// - None if the move is invalid
     /// - Some(State) if the move is valid
-    fn try_move(&self, direction: &Direction, take: &[&Item]) -> Option<Self> {
-        let next_floor = match self.next_floor(direction) {
-            Some(n) => n,
-            None => return None,
+    fn try_move(&self, next: &Move, model: &Model) -> Option<Self> {
+        let next_floor = match next.direction {
+            Direction::Up => Some(self.elevator + 1).filter(|&floor| floor < model.floors)?,
+            Direction::Down => self.elevator.checked_sub(1)?,
         };
 
-        let take: BTreeSet<Item> = take.iter().map(|&i| i.clone()).collect();
-
-        // move the items to the new floor
-        let mut floors = self.floors.clone();
-        floors[self.elevator] = floors[self.elevator].difference(&take).cloned().collect();
-        floors[next_floor] = floors[next_floor].union(&take).cloned().collect();
+        let mut next_state = self.clone();
+        next_state.elevator = next_floor;
 
-        let next_state = State {
-            elevator: next_floor,
-            floors,
-        };
-
-        if next_state.is_valid() {
-            Some(next_state)
-        } else {
-            None
+        for item in &next.items {
+            match *item {
+                Item::Generator(element) => next_state.pairs[element].0 = next_floor,
+                Item::Microchip(element) => next_state.pairs[element].1 = next_floor,
+            }
         }
+
+        Some(next_state).filter(|state| model.rules.iter().all(|rule| rule(state)))
     }
 
-    /// check if every floor is valid in this state
-    fn is_valid(&self) -> bool {
-        self.floors
-            .iter()
-            .enumerate()
-            .all(|(i, _)| self.floor_is_valid(i))
+    /// does this state represent the completed state?
+    fn is_complete(&self, model: &Model) -> bool {
+        let top = model.floors - 1;
+        self.elevator == top && self.pairs.iter().all(|&pair| pair == (top, top))
     }
 
-    /// a floor is valid if it doesn't contain a microchip with no matching generator
-    fn floor_is_valid(&self, floor: usize) -> bool {
-        let items = &self.floors[floor];
+    /// Pack the state into an integer, with the elevator in the lowest bits followed by each
+    /// generator and microchip pair, or just sort the pairs if there are too many floors or
+    /// elements to fit in a u64
+    ///
+    /// The pairs are sorted first, because the element names don't matter to the solution. States
+    /// which only differ by swapping elements around pack to the same value, which massively
+    /// cuts down the number of states to search.
+    fn canonical(&self, model: &Model) -> Key {
+        let bits = model.floor_bits();
+
+        let mut pairs = self.pairs.clone();
+        pairs.sort_unstable();
+
+        if bits * (1 + 2 * pairs.len()) > 64 {
+            let elevator = self.elevator;
+            return Key::Sorted(State { elevator, pairs });
+        }
 
-        let mut generators = BTreeSet::new();
-        let mut microchips = BTreeSet::new();
+        let mut packed = 0;
 
-        for item in items {
-            match item {
-                Item::Generator(id) => generators.insert(id),
-                Item::Microchip(id) => microchips.insert(id),
-            };
+        for &(generator, microchip) in pairs.iter().rev() {
+            packed = (packed << bits) | generator as u64;
+            packed = (packed << bits) | microchip as u64;
         }
 
-        generators.is_empty() || microchips.is_subset(&generators)
+        Key::Packed((packed << bits) | self.elevator as u64)
     }
 
-    /// does this state represent the completed state?
-    fn is_complete(&self) -> bool {
-        self.elevator == 3
-            && self.floors[0].is_empty()
-            && self.floors[1].is_empty()
-            && self.floors[2].is_empty()
-            && !self.floors[3].is_empty()
+    /// Unpack a state packed by [`State::canonical`]
+    fn from_canonical(key: &Key, elements: usize, model: &Model) -> Self {
+        let mut packed = match key {
+            Key::Packed(packed) => *packed,
+            Key::Sorted(state) => return state.clone(),
+        };
+
+        let bits = model.floor_bits();
+        let mask = (1 << bits) - 1;
+        let mut next = || {
+            let floor = (packed & mask) as usize;
+            packed >>= bits;
+            floor
+        };
+
+        let elevator = next();
+        let pairs = (0..elements)
+            .map(|_| {
+                let microchip = next();
+                let generator = next();
+                (generator, microchip)
+            })
+            .collect();
+
+        State { elevator, pairs }
     }
 
-    /// a heuristic which causes the solution to prioritise 'better' states over others
-    fn priority_heuristic(&self) -> usize {
-        // progressively penalise states with more items on lower floors
-        let distances = self.floors[0].len() * 3 + self.floors[1].len() * 2 + self.floors[2].len();
+    /// A lower bound on the number of moves left, so that the A* search finds the shortest route
+    ///
+    /// Every item below a floor has to cross the boundary above it. The elevator carries at most
+    /// c items up, and has to bring at least 1 back down for the next trip, so moving n items up
+    /// takes at least 1 + 2 * ceil((n - c) / (c - 1)) crossings, e.g. 2n - 3 when c is 2. It takes
+    /// one more crossing, and one more item to move, if the elevator has to come down first.
+    fn lower_bound(&self, model: &Model) -> usize {
+        let crossings = |n: usize| match model.capacity {
+            // the elevator can only ever bring back what it took up
+            c if c <= 1 => n,
+            c if n <= c => 1,
+            c => 1 + 2 * (n - c).div_ceil(c - 1),
+        };
 
-        // this multipler was found via trial and error to make it faster
-        // until it starts returning the wrong answer
-        distances * 4
+        (0..model.floors - 1)
+            .map(|boundary| {
+                let below = self
+                    .pairs
+                    .iter()
+                    .map(|&(generator, microchip)| {
+                        (generator <= boundary) as usize + (microchip <= boundary) as usize
+                    })
+                    .sum::<usize>();
+
+                match below {
+                    0 => 0,
+                    n if self.elevator > boundary => 1 + crossings(n + 1),
+                    n => crossings(n),
+                }
+            })
+            .sum()
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub struct QueuedState {
-    steps: usize,
-    state: State,
+/// A state with its elements sorted, packed into an integer when it fits
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Packed(u64),
+    Sorted(State),
+}
+
+/// Every canonical state reachable from another by moving the elevator
+struct Canonical<'a> {
+    elements: usize,
+    model: &'a Model,
 }
 
-impl Ord for QueuedState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // use a heuristic to make sure that the queue gets sorted by priority properly
-        let left = self.steps + self.state.priority_heuristic();
-        let right = other.steps + other.state.priority_heuristic();
+impl Graph for Canonical<'_> {
+    type Node = Key;
 
-        left.cmp(&right).reverse()
+    fn neighbours(&self, key: &Key) -> Vec<Key> {
+        State::from_canonical(key, self.elements, self.model)
+            .valid_moves(self.model)
+            .iter()
+            .map(|(_, state)| state.canonical(self.model))
+            .collect()
     }
 }
 
-impl PartialOrd for QueuedState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+/// Find the shortest sequence of moves to get from the input state to the completed state
+pub fn search(input: &State, model: &Model) -> Option<Vec<Move>> {
+    let elements = input.pairs.len();
+    let unpack = |key: &Key| State::from_canonical(key, elements, model);
+
+    let path = search::astar(
+        &Canonical { elements, model },
+        input.canonical(model),
+        |key| unpack(key).is_complete(model),
+        |key| unpack(key).lower_bound(model),
+    )?;
+
+    // the path only has canonical states, so replay it from the input to find which actual
+    // items were moved at each step, which only fails if a rule broke the contract on [`Rule`]
+    let mut state = input.clone();
+    let mut moves = Vec::new();
+
+    for key in &path.nodes[1..] {
+        let (next, next_state) = state
+            .valid_moves(model)
+            .into_iter()
+            .find(|(_, next_state)| next_state.canonical(model) == *key)?;
+
+        moves.push(next);
+        state = next_state;
     }
+
+    Some(moves)
 }
 
-/// Calculate the minimum number of steps to get from the input state to the completed state
-fn search(input: &State) -> usize {
-    let mut visited = BTreeSet::new();
-    let mut queue = BinaryHeap::new();
-    queue.push(QueuedState {
-        steps: 0,
-        state: input.clone(),
-    });
+impl Facility {
+    /// A short label for an item, e.g. `HM` for the hydrogen-compatible microchip
+    ///
+    /// Labels use the shortest prefix of the element names which tells them all apart
+    fn label(&self, item: &Item) -> String {
+        let prefix = (1..)
+            .find(|&length| {
+                let mut prefixes = self
+                    .elements
+                    .iter()
+                    .map(|name| name.chars().take(length).collect::<String>())
+                    .collect::<Vec<_>>();
+                prefixes.sort_unstable();
+                prefixes.dedup();
+                prefixes.len() == self.elements.len()
+                    || self
+                        .elements
+                        .iter()
+                        .all(|name| name.chars().count() < length)
+            })
+            .unwrap();
+
+        let (element, suffix) = match *item {
+            Item::Generator(element) => (element, 'G'),
+            Item::Microchip(element) => (element, 'M'),
+        };
+
+        let mut chars = self.elements[element].chars().take(prefix);
+        let mut label = chars
+            .next()
+            .map(|c| c.to_ascii_uppercase())
+            .into_iter()
+            .collect::<String>();
+        label.extend(chars);
+        label.push(suffix);
+        label
+    }
 
-    while let Some(QueuedState { steps, state }) = queue.pop() {
-        if state.is_complete() {
-            return steps;
+    /// Draw the floors from the top down in the style of the puzzle, e.g.
+    ///
+    /// ```text
+    /// F4 .  .  .  .  .
+    /// F3 .  .  .  LG .
+    /// F2 .  HG .  .  .
+    /// F1 E  .  HM .  LM
+    /// ```
+    pub fn render(&self, state: &State) -> String {
+        let items = (0..state.pairs.len())
+            .flat_map(|element| vec![Item::Generator(element), Item::Microchip(element)])
+            .map(|item| (self.label(&item), item))
+            .collect::<Vec<_>>();
+        let width = items
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or(1)
+            + 1;
+
+        let mut output = String::new();
+
+        for floor in (0..self.model.floors).rev() {
+            let mut line = format!("F{} ", floor + 1);
+            let elevator = if state.elevator == floor { "E" } else { "." };
+            write!(line, "{:width$}", elevator, width = width).unwrap();
+
+            for (label, item) in &items {
+                let here = match *item {
+                    Item::Generator(element) => state.pairs[element].0 == floor,
+                    Item::Microchip(element) => state.pairs[element].1 == floor,
+                };
+                let cell = if here { label.as_str() } else { "." };
+                write!(line, "{:width$}", cell, width = width).unwrap();
+            }
+
+            writeln!(output, "{}", line.trim_end()).unwrap();
         }
 
-        // breadth-first search along all possible unseen moves
-        let movements = state.valid_moves();
+        output
+    }
 
-        for m in movements.into_iter() {
-            if !visited.contains(&m) {
-                queue.push(QueuedState {
-                    steps: steps + 1,
-                    state: m,
-                });
-            }
+    /// Draw the floors after each move, starting with the initial state
+    pub fn render_moves(&self, moves: &[Move]) -> String {
+        let mut state = self.start.clone();
+        let mut output = self.render(&state);
+
+        for next in moves {
+            let items = next
+                .items
+                .iter()
+                .map(|item| self.label(item))
+                .collect::<Vec<_>>();
+
+            state = state.try_move(next, &self.model).expect("Invalid move");
+
+            writeln!(
+                output,
+                "\n{:?} with {}:",
+                next.direction,
+                items.join(" and ")
+            )
+            .unwrap();
+            output.push_str(&self.render(&state));
         }
 
-        visited.insert(state);
+        output
     }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseFacilityError {
+    /// The input has no lines, so there are no floors to put things on
+    NoFloors,
 
-    panic!("Finished searching and found no solution");
+    /// The element only has one of its generator and microchip
+    Unpaired(String),
 }
 
+impl fmt::Display for ParseFacilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFacilityError::NoFloors => write!(f, "the facility needs at least one floor"),
+            ParseFacilityError::Unpaired(name) => {
+                write!(f, "{} needs both a generator and a microchip", name)
+            }
+        }
+    }
+}
+
+impl Error for ParseFacilityError {}
+
 #[aoc_generator(day11)]
-pub fn generator(input: &str) -> State {
+pub fn generator(input: &str) -> Result<Facility, ParseFacilityError> {
     lazy_static! {
         static ref GENERATOR: Regex = Regex::new(r"(\w+) generator").unwrap();
         static ref MICROCHIP: Regex = Regex::new(r"(\w+)-compatible microchip").unwrap();
     }
 
-    let mut state = State {
-        elevator: 0,
-        floors: [
-            BTreeSet::new(),
-            BTreeSet::new(),
-            BTreeSet::new(),
-            BTreeSet::new(),
-        ],
-    };
+    let mut elements = BTreeMap::new();
+    let floors = input.lines().count();
+
+    if floors == 0 {
+        return Err(ParseFacilityError::NoFloors);
+    }
 
     input.lines().enumerate().for_each(|(i, line)| {
         for generator in GENERATOR.captures_iter(line) {
-            state.floors[i].insert(Item::Generator(generator[1].to_owned()));
+            elements
+                .entry(generator[1].to_owned())
+                .or_insert((None, None))
+                .0 = Some(i);
         }
 
         for microchip in MICROCHIP.captures_iter(line) {
-            state.floors[i].insert(Item::Microchip(microchip[1].to_owned()));
+            elements
+                .entry(microchip[1].to_owned())
+                .or_insert((None, None))
+                .1 = Some(i);
         }
     });
 
-    state
+    let (elements, pairs) = elements
+        .into_iter()
+        .map(|(name, pair)| match pair {
+            (Some(generator), Some(microchip)) => Ok((name, (generator, microchip))),
+            _ => Err(ParseFacilityError::Unpaired(name)),
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+
+    Ok(Facility {
+        elements,
+        start: State { elevator: 0, pairs },
+        model: Model {
+            floors,
+            ..Model::default()
+        },
+    })
 }
 
 #[aoc(day11, part1)]
-pub fn part1(input: &State) -> usize {
-    search(input)
+pub fn part1(input: &Facility) -> usize {
+    search(&input.start, &input.model)
+        .expect("Finished searching and found no solution")
+        .len()
 }
 
+// release mode - ~10ms
 #[aoc(day11, part2)]
-pub fn part2(input: &State) -> usize {
+pub fn part2(input: &Facility) -> usize {
     let mut input = input.clone();
-    input.floors[0].insert(Item::Generator("elerium".into()));
-    input.floors[0].insert(Item::Microchip("elerium".into()));
-    input.floors[0].insert(Item::Generator("dilithium ".into()));
-    input.floors[0].insert(Item::Microchip("dilithium ".into()));
 
-    search(&input)
+    for element in &["elerium", "dilithium"] {
+        input.elements.push(element.to_string());
+        input.start.pairs.push((0, 0));
+    }
+
+    part1(&input)
 }
 
 #[cfg(test)]
@@ -250,15 +511,204 @@ mod tests {
 
     static INPUT: &str = include_str!("../input/2016/day11.txt");
 
+    static EXAMPLE: &str = "\
+The first floor contains a hydrogen-compatible microchip and a lithium-compatible microchip.
+The second floor contains a hydrogen generator.
+The third floor contains a lithium generator.
+The fourth floor contains nothing relevant.";
+
+    #[test]
+    fn test_example() {
+        let input = generator(EXAMPLE).unwrap();
+        assert_eq!(input.elements, vec!["hydrogen", "lithium"]);
+        assert_eq!(input.start.pairs, vec![(1, 0), (2, 0)]);
+        assert_eq!(part1(&input), 11);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = generator(EXAMPLE).unwrap();
+
+        assert_eq!(
+            input.render(&input.start),
+            "F4 .  .  .  .  .\n\
+             F3 .  .  .  LG .\n\
+             F2 .  HG .  .  .\n\
+             F1 E  .  HM .  LM\n"
+        );
+
+        let input = generator(INPUT).unwrap();
+        assert_eq!(input.label(&Item::Generator(0)), "PlG");
+        assert_eq!(input.label(&Item::Microchip(1)), "PrM");
+    }
+
+    #[test]
+    fn test_moves() {
+        let input = generator(EXAMPLE).unwrap();
+        let moves = search(&input.start, &input.model).unwrap();
+        assert_eq!(moves.len(), 11);
+
+        // the first move has to be taking the hydrogen microchip up to its generator
+        assert_eq!(
+            moves[0],
+            Move {
+                direction: Direction::Up,
+                items: vec![Item::Microchip(0)],
+            }
+        );
+
+        let rendered = input.render_moves(&moves);
+        assert!(rendered.starts_with(&input.render(&input.start)));
+        assert!(rendered
+            .contains("\nUp with HM:\nF4 .  .  .  .  .\nF3 .  .  .  LG .\nF2 E  HG HM .  .\n"));
+        assert!(rendered.ends_with(
+            "F4 E  HG HM LG LM\nF3 .  .  .  .  .\nF2 .  .  .  .  .\nF1 .  .  .  .  .\n"
+        ));
+    }
+
+    #[test]
+    fn test_canonical() {
+        let model = Model::default();
+        let state = State {
+            elevator: 2,
+            pairs: vec![(3, 1), (0, 2), (1, 1)],
+        };
+        let swapped = State {
+            elevator: 2,
+            pairs: vec![(1, 1), (3, 1), (0, 2)],
+        };
+
+        assert_eq!(state.canonical(&model), swapped.canonical(&model));
+        assert_eq!(
+            State::from_canonical(&state.canonical(&model), 3, &model),
+            State {
+                elevator: 2,
+                pairs: vec![(0, 2), (1, 1), (3, 1)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        let input = generator(INPUT).unwrap();
+        assert!(input.start.lower_bound(&input.model) <= part1(&input));
+
+        let complete = State {
+            elevator: 3,
+            pairs: vec![(3, 3); 2],
+        };
+        assert_eq!(complete.lower_bound(&Model::default()), 0);
+    }
+
+    /// Search without canonicalising states or the heuristic to check the shortcuts are sound
+    fn brute_force(facility: &Facility) -> Option<usize> {
+        struct Named<'a>(&'a Model);
+
+        impl Graph for Named<'_> {
+            type Node = State;
+
+            fn neighbours(&self, state: &State) -> Vec<State> {
+                let moves = state.valid_moves(self.0);
+                moves.into_iter().map(|(_, state)| state).collect()
+            }
+        }
+
+        let model = &facility.model;
+        search::bfs(&Named(model), facility.start.clone(), |s| {
+            s.is_complete(model)
+        })
+        .map(|path| path.steps())
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        assert_eq!(generator("").unwrap_err(), ParseFacilityError::NoFloors);
+        assert_eq!(
+            generator("The first floor contains a thulium generator.").unwrap_err(),
+            ParseFacilityError::Unpaired("thulium".to_string())
+        );
+    }
+
+    #[test]
+    fn test_model() {
+        let input = generator(EXAMPLE).unwrap();
+        assert_eq!(input.model.floors, 4);
+        assert_eq!(input.model.capacity, 2);
+
+        let mut bigger = input.clone();
+        bigger.model.capacity = 3;
+
+        let taller = generator(&format!(
+            "{}\nThe fifth floor contains nothing relevant.",
+            EXAMPLE
+        ))
+        .unwrap();
+        assert_eq!(taller.model.floors, 5);
+        assert!(taller
+            .render(&taller.start)
+            .starts_with("F5 .  .  .  .  .\nF4"));
+
+        // without the fry rule, anything goes
+        let mut safe = input.clone();
+        safe.model.rules.clear();
+
+        // a rule that stops generators reaching the top floor makes it impossible
+        let mut stuck = input.clone();
+        stuck
+            .model
+            .rules
+            .push(|state| state.pairs().iter().all(|&(g, _)| g < 3));
+
+        let facilities = [input, bigger, taller, safe, stuck];
+        let expected = [Some(11), Some(9), Some(16), Some(9), None];
+
+        for (facility, expected) in facilities.iter().zip(&expected) {
+            let moves = search(&facility.start, &facility.model).map(|moves| moves.len());
+            assert_eq!(moves, *expected);
+            assert_eq!(moves, brute_force(facility));
+        }
+    }
+
+    #[test]
+    fn test_wide_states() {
+        // 40 floors need 6 bits each, so 5 elements and the elevator take 66 bits
+        let model = Model {
+            floors: 40,
+            ..Model::default()
+        };
+        let state = State {
+            elevator: 38,
+            pairs: vec![(38, 38), (38, 37), (37, 37), (38, 38), (37, 38)],
+        };
+
+        let key = state.canonical(&model);
+        assert!(matches!(key, Key::Sorted(_)));
+        assert_eq!(State::from_canonical(&key, 5, &model).pairs[0], (37, 37));
+
+        // the floors below shouldn't matter, so it's the same as just the top two floors
+        let moves = search(&state, &model).unwrap().len();
+
+        let model = Model {
+            floors: 3,
+            ..Model::default()
+        };
+        let state = State {
+            elevator: 1,
+            pairs: state.pairs.iter().map(|&(g, m)| (g - 37, m - 37)).collect(),
+        };
+        assert!(matches!(state.canonical(&model), Key::Packed(_)));
+        assert_eq!(search(&state, &model).unwrap().len(), moves);
+    }
+
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), 31);
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part2(&input), 55);
     }
 }
@@ -1,82 +1,29 @@
-use crate::{compass::Point, Part};
-use aoc_runner_derive::{aoc, aoc_generator};
-use std::{
-    collections::{BTreeSet, BinaryHeap},
-    num::ParseIntError,
+use crate::{
+    compass::Point,
+    search::{self, Graph},
 };
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::num::ParseIntError;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct QueuedPoint {
-    steps: usize,
-    point: Point,
-}
-
-impl Ord for QueuedPoint {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // use a heuristic to make sure that the queue gets sorted by priority properly
-        self.steps.cmp(&other.steps).reverse()
-    }
-}
-
-impl PartialOrd for QueuedPoint {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn shortest_path(input: usize, part: &Part, target: &Point) -> usize {
-    let mut visited = BTreeSet::new();
-    let mut queue = BinaryHeap::new();
-
-    queue.push(QueuedPoint {
-        steps: 0,
-        point: Point::new(1, 1),
-    });
-
-    while let Some(QueuedPoint { steps, point }) = queue.pop() {
-        match part {
-            Part::One => {
-                if &point == target {
-                    return steps;
-                }
-            }
-            Part::Two => {
-                if steps > 50 {
-                    continue;
-                }
-            }
-        }
-
-        // breadth-first search along all possible unseen moves
-        let movements = valid_moves(&point, input);
-
-        for m in movements.into_iter() {
-            if !visited.contains(&m) {
-                queue.push(QueuedPoint {
-                    steps: steps + 1,
-                    point: m,
-                });
-            }
-        }
+/// The office maze, generated from the designer's favourite number
+struct Maze(usize);
 
-        visited.insert(point);
-    }
+impl Graph for Maze {
+    type Node = Point;
 
-    match part {
-        Part::One => panic!("Ran out of moves to make without reaching target point"),
-        Part::Two => visited.len(),
+    fn neighbours(&self, point: &Point) -> Vec<Point> {
+        valid_moves(point, self.0)
     }
 }
 
 /// Get all the points which are still on the map and are open spaces
 fn valid_moves(point: &Point, input: usize) -> Vec<Point> {
-    let deltas = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-
-    deltas
+    point
+        .neighbours_4()
         .iter()
-        .map(|d| Point::new(point.x + d.0, point.y + d.1))
+        .copied()
         .filter(|p| p.x >= 0 && p.y >= 0)
-        .filter(|p| is_open(&p, input))
+        .filter(|p| is_open(p, input))
         .collect()
 }
 
@@ -101,12 +48,16 @@ pub fn generator(input: &str) -> Result<usize, ParseIntError> {
 
 #[aoc(day13, part1)]
 pub fn part1(input: &usize) -> usize {
-    shortest_path(*input, &Part::One, &Point::new(31, 39))
+    let target = Point::new(31, 39);
+
+    search::bfs(&Maze(*input), Point::new(1, 1), |p| *p == target)
+        .expect("Ran out of moves to make without reaching target point")
+        .steps()
 }
 
 #[aoc(day13, part2)]
 pub fn part2(input: &usize) -> usize {
-    shortest_path(*input, &Part::Two, &Point::new(31, 39))
+    search::distances(&Maze(*input), Point::new(1, 1), 50).len()
 }
 
 #[cfg(test)]
@@ -1,54 +1,163 @@
+use crate::number_theory::{self, Congruence, CongruenceError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit1, space0, space1},
+    combinator::{all_consuming, map_res, opt, verify},
+    error::{context, VerboseError, VerboseErrorKind},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+use std::{convert::TryFrom, error::Error, fmt};
 
-/// I know this is lowest-common multiple with modular arithmetic, but I'm going
-/// to brute force it anyway :)
-
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Disc {
-    id: usize,
-    position: usize,
-    size: usize,
+    pub id: usize,
+    pub size: usize,
+
+    /// The position the disc is at at `time`
+    pub position: usize,
+    pub time: usize,
+}
+
+impl Disc {
+    /// The times the capsule could be dropped so this disc is at position 0 when it arrives
+    fn drop_times(&self) -> Congruence {
+        // the disc was at position - time at time 0, and the capsule reaches disc n after n
+        // seconds, so dropping at time t needs position - time + n + t ≡ 0 (mod size)
+        let offset = self.position as i128 - self.time as i128 + self.id as i128;
+        Congruence::new(-offset, self.size as i128)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiscError {
+    /// Line number in the input, starting from 1
+    pub line: usize,
+
+    /// Column where parsing failed, starting from 1
+    pub column: usize,
+
+    /// Description of what was expected at that column
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ParseDiscError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl Error for ParseDiscError {}
+
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+fn number<'a>(description: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, usize> {
+    context(description, map_res(digit1, str::parse))
+}
+
+/// Allows any amount of whitespace around a parser
+fn spaced<'a, T>(
+    parser: impl FnMut(&'a str) -> ParseResult<'a, T>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, T> {
+    delimited(space0, parser, space0)
+}
+
+/// Parses a string like:
+///
+/// Disc #1 has 5 positions; at time=0, it is at position 4.
+///
+/// into a Disc, where the full stop at the end is optional
+fn parse_disc(s: &str) -> ParseResult<'_, Disc> {
+    let disc = pair(tag("Disc"), preceded(space1, char('#')));
+    let (s, _) = spaced(context("`Disc #`", disc))(s)?;
+    let (s, id) = number("the disc number")(s)?;
+
+    let (s, _) = spaced(context("`has`", tag("has")))(s)?;
+    let size = verify(number("the number of positions"), |&size| size > 0);
+    let (s, size) = context("at least one position", size)(s)?;
+
+    let positions = pair(
+        alt((tag("positions"), tag("position"))),
+        preceded(space0, char(';')),
+    );
+    let (s, _) = spaced(context("`positions;`", positions))(s)?;
+
+    let at_time = pair(tag("at"), preceded(space1, tag("time=")));
+    let (s, _) = spaced(context("`at time=`", at_time))(s)?;
+    let (s, time) = number("the time")(s)?;
+
+    let at_position = tuple((char(','), space0, tag("it is at position")));
+    let (s, _) = spaced(context("`, it is at position`", at_position))(s)?;
+    let (s, position) = number("the disc's position")(s)?;
+
+    let end = all_consuming(spaced(opt(char('.'))));
+    let (s, _) = context("the end of the line", end)(s)?;
+
+    let disc = Disc {
+        id,
+        size,
+        position,
+        time,
+    };
+
+    Ok((s, disc))
 }
 
 #[aoc_generator(day15)]
-pub fn generator(input: &str) -> Vec<Disc> {
-    // Disc #1 has 5 positions; at time=0, it is at position 4.
-
-    input.lines().map(|l| match l.trim().split(' ').collect::<Vec<_>>()[..] {
-        ["Disc", id, "has", size, "positions;", "at", "time=0,", "it", "is", "at", "position", position] => {
-            Disc {
-                id: id[1..].parse().unwrap(),
-                position: position[..position.len()-1].parse().unwrap(),
-                size: size.parse().unwrap(),
+pub fn generator(input: &str) -> Result<Vec<Disc>, ParseDiscError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| match parse_disc(line) {
+            Ok((_, disc)) => Ok(disc),
+            Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                // the first error is where parsing stopped, and the first context is the most
+                // specific description of what should have been there
+                let remaining = e.errors.first().map_or("", |(remaining, _)| *remaining);
+                let expected = e
+                    .errors
+                    .iter()
+                    .find_map(|(_, kind)| match kind {
+                        VerboseErrorKind::Context(context) => Some(*context),
+                        _ => None,
+                    })
+                    .unwrap_or("a disc");
+
+                Err(ParseDiscError {
+                    line: index + 1,
+                    column: line.len() - remaining.len() + 1,
+                    expected,
+                })
             }
-        },
-        _ => panic!("Unexpected line: {}", l)
-    })
-    .collect()
+            Err(nom::Err::Incomplete(_)) => unreachable!("Complete parsers never need more input"),
+        })
+        .collect()
 }
 
+/// The first time the capsule can be dropped to fall through every disc, or why there isn't one
+/// (including times too big for a usize)
 #[aoc(day15, part1)]
-pub fn part1(input: &[Disc]) -> usize {
-    for i in 0.. {
-        if input
-            .iter()
-            .all(|disc| (disc.id + i + disc.position) % disc.size == 0)
-        {
-            return i;
-        }
-    }
-
-    unreachable!()
+pub fn part1(input: &[Disc]) -> Result<usize, CongruenceError> {
+    let times = number_theory::crt(input.iter().map(Disc::drop_times))?;
+    usize::try_from(times.remainder).map_err(|_| CongruenceError::Overflow)
 }
 
 #[aoc(day15, part2)]
-pub fn part2(input: &[Disc]) -> usize {
+pub fn part2(input: &[Disc]) -> Result<usize, CongruenceError> {
     let mut input = input.to_vec();
 
     let extra = Disc {
         id: input.len() + 1,
-        position: 0,
         size: 11,
+        position: 0,
+        time: 0,
     };
 
     input.push(extra);
@@ -64,13 +173,126 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
-        assert_eq!(part1(&input), 16824);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(16824));
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
-        assert_eq!(part2(&input), 3543984);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part2(&input), Ok(3543984));
+    }
+
+    #[test]
+    fn test_example() {
+        let input = generator(
+            "Disc #1 has 5 positions; at time=0, it is at position 4.
+             Disc #2 has 2 positions; at time=0, it is at position 1.",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), Ok(5));
+    }
+
+    #[test]
+    fn test_generator() {
+        let disc = Disc {
+            id: 1,
+            size: 5,
+            position: 4,
+            time: 0,
+        };
+
+        assert_eq!(
+            generator("Disc #1 has 5 positions; at time=0, it is at position 4."),
+            Ok(vec![disc])
+        );
+        assert_eq!(
+            generator("  Disc  #1 has 5 positions ;at time=0 ,it is at position 4  "),
+            Ok(vec![disc])
+        );
+
+        assert_eq!(
+            generator("Disc #1 has 5 positions; at time=0, it is at position 4.\nDisc #2 has five"),
+            Err(ParseDiscError {
+                line: 2,
+                column: 13,
+                expected: "the number of positions"
+            })
+        );
+        assert_eq!(
+            generator("Disc #1 has 5 positions; at time=0, it is at position 4!")
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 56: expected the end of the line"
+        );
+        assert_eq!(
+            generator("Disc #1 has 0 positions; at time=0, it is at position 0."),
+            Err(ParseDiscError {
+                line: 1,
+                column: 13,
+                expected: "at least one position"
+            })
+        );
+    }
+
+    #[test]
+    fn test_start_time() {
+        // the same discs as the example, but described a few seconds later
+        let input = generator(
+            "Disc #1 has 5 positions; at time=3, it is at position 2.
+             Disc #2 has 2 positions; at time=7, it is at position 0.",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), Ok(5));
+    }
+
+    #[test]
+    fn test_large_discs() {
+        let input = generator(
+            "Disc #1 has 1000000007 positions; at time=0, it is at position 3.
+             Disc #2 has 998244353 positions; at time=0, it is at position 5.",
+        )
+        .unwrap();
+        let time = part1(&input).unwrap();
+
+        assert!(input
+            .iter()
+            .all(|disc| (disc.id + time + disc.position).is_multiple_of(disc.size)));
+
+        // the combined cycle of five discs this size is too long to work with
+        let sizes = [
+            1_000_000_007,
+            998_244_353,
+            2_147_483_647,
+            1_000_000_009,
+            999_999_937,
+        ];
+        let input = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| {
+                format!(
+                    "Disc #{} has {} positions; at time=0, it is at position 0.",
+                    i + 1,
+                    size
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            part1(&generator(&input).unwrap()),
+            Err(CongruenceError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_impossible() {
+        // both discs have an even number of positions, but need opposite parities of time
+        let input = generator(
+            "Disc #1 has 4 positions; at time=0, it is at position 0.
+             Disc #2 has 6 positions; at time=0, it is at position 0.",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), Err(CongruenceError::NoSolution));
     }
 }
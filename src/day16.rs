@@ -6,12 +6,107 @@ pub fn part1(input: &str) -> String {
     generate_checksum(input, 272)
 }
 
+/// See `cargo bench --bench day16` for how this compares to building the whole disk
 #[aoc(day16, part2)]
 pub fn part2(input: &str) -> String {
     generate_checksum(input, 35651584)
 }
 
-fn generate_checksum(input: &str, length: usize) -> String {
+/// The disk filled with the dragon curve, without ever building it
+///
+/// The disk is the initial state `a` and its reversed inverse `b` repeating as `a ? b ? a ? b ...`,
+/// where the `?` separators are the regular paperfolding sequence `0010011...`
+struct Dragon {
+    /// `prefix[i]` is the number of ones in the first `i` characters of the initial state
+    prefix: Vec<usize>,
+}
+
+impl Dragon {
+    fn new(initial: &str) -> Self {
+        let prefix = std::iter::once(0)
+            .chain(initial.chars().scan(0, |ones, c| {
+                *ones += (c == '1') as usize;
+                Some(*ones)
+            }))
+            .collect();
+
+        Dragon { prefix }
+    }
+
+    /// Number of ones in the first `n` characters of the disk
+    fn ones(&self, n: usize) -> usize {
+        let length = self.prefix.len() - 1;
+        let a = self.prefix[length];
+        let b = length - a;
+
+        // complete blocks and the separators after them, then part of the next block
+        let blocks = n / (length + 1);
+        let partial = n % (length + 1);
+
+        let full = blocks.div_ceil(2) * a + blocks / 2 * b + separator_ones(blocks);
+
+        let rest = if blocks.is_multiple_of(2) {
+            self.prefix[partial]
+        } else {
+            // the start of b is the inverted end of a
+            partial - (a - self.prefix[length - partial])
+        };
+
+        full + rest
+    }
+}
+
+/// Number of ones in the first `n` separators
+///
+/// The `i`th separator (from 1) is one when the odd part of `i` is 3 mod 4, so count the odd
+/// numbers like that up to `n / 2^k` for every `k`
+fn separator_ones(n: usize) -> usize {
+    let mut ones = 0;
+    let mut n = n;
+
+    while n > 0 {
+        ones += (n + 1) / 4;
+        n /= 2;
+    }
+
+    ones
+}
+
+/// The checksum of a disk of the given length filled from the initial state
+pub fn generate_checksum(input: &str, length: usize) -> String {
+    if length == 0 {
+        return String::new();
+    }
+
+    let dragon = Dragon::new(input.trim());
+
+    // halving is repeated while the length is even, so each checksum digit covers a chunk the
+    // size of the largest power of two dividing the length
+    let chunk = 1 << length.trailing_zeros();
+
+    (0..length / chunk)
+        .map(|i| {
+            let ones = dragon.ones((i + 1) * chunk) - dragon.ones(i * chunk);
+
+            // each halving is an XNOR of pairs, so a chunk of two or more reduces to 1 when it has
+            // an even number of ones, but a single character is left as it is
+            let even = ones.is_multiple_of(2);
+            if even == (chunk > 1) {
+                '1'
+            } else {
+                '0'
+            }
+        })
+        .collect()
+}
+
+/// Fills the disk and halves the checksum exactly as the puzzle describes, which is much slower
+/// and needs memory for the whole disk, but is useful to check [`generate_checksum`] against
+pub fn naive_checksum(input: &str, length: usize) -> String {
+    if length == 0 {
+        return String::new();
+    }
+
     let mut a = input.trim().to_owned();
 
     while a.len() < length {
@@ -27,7 +122,7 @@ fn generate_checksum(input: &str, length: usize) -> String {
 
     let mut checksum = a[..length].to_owned();
 
-    while checksum.len() % 2 == 0 {
+    while checksum.len().is_multiple_of(2) {
         checksum = checksum
             .chars()
             .tuples()
@@ -44,6 +139,38 @@ mod tests {
 
     static INPUT: &str = include_str!("../input/2016/day16.txt");
 
+    #[test]
+    fn test_example() {
+        assert_eq!(generate_checksum("10000", 20), "01100");
+        assert_eq!(generate_checksum("110010110100", 12), "100");
+        assert_eq!(generate_checksum("10000", 0), "");
+    }
+
+    #[test]
+    fn test_naive() {
+        for input in &["0", "1", "10000", "110010110100", "1110", INPUT] {
+            for length in 0..300 {
+                assert_eq!(
+                    generate_checksum(input, length),
+                    naive_checksum(input, length),
+                    "{} {}",
+                    input,
+                    length
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_separator_ones() {
+        let separators = "0010011000110110001001110011011";
+
+        for n in 0..=separators.len() {
+            let expected = separators[..n].chars().filter(|&c| c == '1').count();
+            assert_eq!(separator_ones(n), expected);
+        }
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(INPUT), "10100101010101101");
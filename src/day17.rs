@@ -1,139 +1,71 @@
-use crate::compass::{Direction, Point};
-use aoc_runner_derive::aoc;
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashSet},
+use crate::{
+    compass::{Convention, Direction, Point, Rect},
+    search::{self, Graph},
 };
+use aoc_runner_derive::aoc;
 
-#[derive(Clone, Eq, PartialEq)]
-struct State {
-    point: Point,
-    path: String,
-}
+/// The 4x4 grid of rooms, where the doors which are open depend on the path taken so far
+struct Vault;
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.path.len().cmp(&other.path.len()).reverse()
-    }
-}
+/// The current room and the passcode followed by the path taken to reach it
+type Room = (Point, String);
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+impl Vault {
+    const ROOMS: Rect = Rect::new(Point::new(0, 0), Point::new(3, 3));
+    const TARGET: Point = Point::new(3, 3);
 }
 
-#[aoc(day17, part1)]
-pub fn part1(input: &str) -> Option<String> {
-    let input = input.trim();
-    let target = Point::new(3, 3);
-    let mut heap = BinaryHeap::new();
-
-    heap.push(State {
-        point: Point::default(),
-        path: input.to_owned(),
-    });
-
-    while let Some(State { point, path }) = heap.pop() {
-        if point == target {
-            // since the heap is ordered by shortest, this is guaranteed to be the shortest path
-            return Some(path[input.len()..].to_string());
-        }
-
-        let md5 = md5::compute(&path);
-        let md5 = format!("{:x}", md5);
-        let mut chars = md5.chars();
-
-        // y axis is flipped because (0,0) is top-left of the maze, not bottom-left
-        if chars.next().unwrap() > 'a' && point.y > 0 {
-            heap.push(State {
-                point: point.move_direction_steps(&Direction::North, -1),
-                path: format!("{}U", path),
-            });
-        }
+impl Graph for Vault {
+    type Node = Room;
 
-        if chars.next().unwrap() > 'a' && point.y < 3 {
-            heap.push(State {
-                point: point.move_direction_steps(&Direction::South, -1),
-                path: format!("{}D", path),
-            });
+    fn neighbours(&self, (point, path): &Room) -> Vec<Room> {
+        // the vault is the end of the route, so there's no going any further
+        if *point == Vault::TARGET {
+            return Vec::new();
         }
 
-        if chars.next().unwrap() > 'a' && point.x > 0 {
-            heap.push(State {
-                point: point.move_direction(&Direction::West),
-                path: format!("{}L", path),
-            });
-        }
+        let md5 = md5::compute(path);
+        let md5 = format!("{:x}", md5);
 
-        if chars.next().unwrap() > 'a' && point.x < 3 {
-            heap.push(State {
-                point: point.move_direction(&Direction::East),
-                path: format!("{}R", path),
-            });
-        }
+        md5.chars()
+            .zip("UDLR".chars())
+            .filter(|(c, _)| *c > 'a')
+            .map(|(_, step)| {
+                let direction = step.to_string().parse::<Direction>().unwrap();
+
+                // (0,0) is top-left of the maze, not bottom-left
+                let next = point.step(&direction, Convention::YDown);
+                (next, format!("{}{}", path, step))
+            })
+            .filter(|(next, _)| Vault::ROOMS.contains(next))
+            .collect()
     }
+}
 
-    None
+#[aoc(day17, part1)]
+pub fn part1(input: &str) -> Option<String> {
+    let input = input.trim();
+    let start = (Point::default(), input.to_owned());
+
+    search::bfs(&Vault, start, |(point, _)| *point == Vault::TARGET)
+        .map(|path| path.goal().1[input.len()..].to_string())
 }
 
 /// find every possible path, then return the length of the longest
 #[aoc(day17, part2)]
 pub fn part2(input: &str) -> Option<usize> {
     let input = input.trim();
-    let mut paths = HashSet::new();
-
-    walk(Point::default(), input, &mut paths);
 
-    paths
-        .iter()
-        .map(|path| path.len())
-        .max()
-        .map(|m| m - input.len())
+    longest(&(Point::default(), input.to_owned())).map(|m| m - input.len())
 }
 
-fn walk(current: Point, path: &str, paths: &mut HashSet<String>) {
-    if current == Point::new(3, 3) {
-        paths.insert(path.to_string());
-        return;
-    }
-
-    let md5 = md5::compute(&path);
-    let md5 = format!("{:x}", md5);
-    let mut chars = md5.chars();
-
-    // y axis is flipped because (0,0) is top-left of the maze, not bottom-left
-    if chars.next().unwrap() > 'a' && current.y > 0 {
-        walk(
-            current.move_direction_steps(&Direction::North, -1),
-            &format!("{}U", path),
-            paths,
-        );
+/// Walk every route to the vault, returning the length of the longest path
+fn longest(room: &Room) -> Option<usize> {
+    if room.0 == Vault::TARGET {
+        return Some(room.1.len());
     }
 
-    if chars.next().unwrap() > 'a' && current.y < 3 {
-        walk(
-            current.move_direction_steps(&Direction::South, -1),
-            &format!("{}D", path),
-            paths,
-        );
-    }
-
-    if chars.next().unwrap() > 'a' && current.x > 0 {
-        walk(
-            current.move_direction(&Direction::West),
-            &format!("{}L", path),
-            paths,
-        );
-    }
-
-    if chars.next().unwrap() > 'a' && current.x < 3 {
-        walk(
-            current.move_direction(&Direction::East),
-            &format!("{}R", path),
-            paths,
-        );
-    }
+    Vault.neighbours(room).iter().filter_map(longest).max()
 }
 
 #[cfg(test)]
@@ -2,55 +2,153 @@ use aoc_runner_derive::aoc;
 
 #[aoc(day18, part1)]
 pub fn part1(input: &str) -> usize {
-    calculate_safe_tiles(input, 40)
+    safe_tiles(&Row::new(input.trim()), 40)
 }
 
 #[aoc(day18, part2)]
 pub fn part2(input: &str) -> usize {
-    calculate_safe_tiles(input, 400_000)
+    safe_tiles(&Row::new(input.trim()), 400_000)
 }
 
-fn calculate_safe_tiles(input: &str, iterations: usize) -> usize {
-    let mut current = input.trim().chars().map(|c| c == '^').collect::<Vec<_>>();
-    let mut safe = current.iter().filter(|&&p| !p).count();
+/// A row of tiles of any width, packed 64 to a word with a set bit for each trap
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Row {
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl Row {
+    /// Create a row from a string like `.^^.^`, where anything other than `^` is safe
+    pub fn new(tiles: &str) -> Self {
+        let width = tiles.chars().count();
+        let mut words = vec![0; width.div_ceil(64)];
+
+        for (i, tile) in tiles.chars().enumerate() {
+            if tile == '^' {
+                words[i / 64] |= 1 << (i % 64);
+            }
+        }
+
+        Row { width, words }
+    }
+
+    pub fn safe(&self) -> usize {
+        let traps = self.words.iter().map(|w| w.count_ones() as usize);
+        self.width - traps.sum::<usize>()
+    }
 
-    for _ in 0..iterations - 1 {
-        current = transform(&current);
-        safe += current.iter().filter(|&&p| !p).count();
+    /// The row below this one
+    ///
+    /// The rules equate to:
+    ///     true    true    false - left and centre
+    ///     true    false   false - left only
+    ///     false   true    true  - centre and right
+    ///     false   false   true  - right only
+    ///
+    /// so the centre value doesn't actually matter, a tile is a trap when left != right. That
+    /// means the whole row is the row shifted one way XOR the row shifted the other, with the bits
+    /// carried across the word boundaries.
+    pub fn next(&self) -> Self {
+        let mut row = self.clone();
+        row.advance();
+        row
     }
 
-    safe
+    /// Replace this row with the one below it, without allocating
+    fn advance(&mut self) {
+        let last = self.words.len().saturating_sub(1);
+
+        // the top bit of the word before this one, before it was overwritten
+        let mut before = 0;
+
+        for i in 0..self.words.len() {
+            let word = self.words[i];
+            let after = if i < last { self.words[i + 1] << 63 } else { 0 };
+
+            self.words[i] = ((word << 1) | before) ^ ((word >> 1) | after);
+            before = word >> 63;
+        }
+
+        // the walls beyond the end of the row count as safe
+        if !self.width.is_multiple_of(64) {
+            self.words[last] &= (1 << (self.width % 64)) - 1;
+        }
+    }
 }
 
-/// Transform the current row into the next row
-fn transform(input: &[bool]) -> Vec<bool> {
-    (0..input.len()).map(|i| is_trap(i, input)).collect()
+impl std::fmt::Display for Row {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for i in 0..self.width {
+            let trap = self.words[i / 64] & (1 << (i % 64)) != 0;
+            write!(f, "{}", if trap { '^' } else { '.' })?;
+        }
+
+        Ok(())
+    }
 }
 
-/// Check if the next row at the given index should be a trap or not
-///
-/// The rules equate to:
-///     true    true    false - left and centre
-///     true    false   false - left only
-///     false   true    true  - centre and right
-///     false   false   true  - right only
+/// Total safe tiles in the first `rows` rows, starting with the given row
 ///
-/// so the centre value doesn't actually matter, it's just the same as left != right
-fn is_trap(i: usize, input: &[bool]) -> bool {
-    let left = if i > 0 {
-        input.get(i - 1).unwrap()
-    } else {
-        &false
-    };
-
-    // manual bounds checking here results in ~5% performance improvement over unwrap_or
-    let right = if i < input.len() - 1 {
-        input.get(i + 1).unwrap()
-    } else {
-        &false
-    };
-
-    left != right
+/// The rows are checked for a cycle with Brent's algorithm as they're counted, and as soon as one
+/// is found only a single lap is counted, so huge numbers of rows can be counted for narrow rows.
+/// Wider rows can take astronomically long to repeat (the 100 tiles in the puzzle input don't
+/// within 2^30 rows), and then the search costs no more than counting every row.
+pub fn safe_tiles(first: &Row, rows: usize) -> usize {
+    if rows == 0 {
+        return 0;
+    }
+
+    let mut safe = first.safe();
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = first.clone();
+    let mut hare = first.next();
+
+    // the index of the hare, which has counted every row before it
+    let mut index = 1;
+
+    while index < rows {
+        if tortoise == hare {
+            // every row from here repeats the one a period before, so count one lap and multiply,
+            // unless there's less than a lap left anyway
+            let left = rows - index;
+
+            if left >= period {
+                let (lap, _) = count(hare.clone(), period);
+                let (rest, _) = count(hare, left % period);
+
+                return safe + left / period * lap + rest;
+            }
+
+            break;
+        }
+
+        safe += hare.safe();
+
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare.advance();
+        period += 1;
+        index += 1;
+    }
+
+    safe + count(hare, rows - index).0
+}
+
+/// The safe tiles in `rows` rows starting with the given row, along with the row after them
+fn count(mut row: Row, rows: usize) -> (usize, Row) {
+    let mut safe = 0;
+
+    for _ in 0..rows {
+        safe += row.safe();
+        row.advance();
+    }
+
+    (safe, row)
 }
 
 #[cfg(test)]
@@ -59,6 +157,107 @@ mod tests {
 
     static INPUT: &str = include_str!("../input/2016/day18.txt");
 
+    /// Builds every row a tile at a time
+    fn naive_safe_tiles(input: &str, rows: usize) -> usize {
+        let mut current = input.chars().map(|c| c == '^').collect::<Vec<_>>();
+        let mut safe = 0;
+
+        for _ in 0..rows {
+            safe += current.iter().filter(|&&p| !p).count();
+            current = (0..current.len())
+                .map(|i| {
+                    let left = i > 0 && current[i - 1];
+                    let right = i + 1 < current.len() && current[i + 1];
+                    left != right
+                })
+                .collect();
+        }
+
+        safe
+    }
+
+    #[test]
+    fn test_example() {
+        let row = Row::new(".^^.^.^^^^");
+
+        assert_eq!(row.next().to_string(), "^^^...^..^");
+        assert_eq!(safe_tiles(&row, 10), 38);
+    }
+
+    #[test]
+    fn test_widths() {
+        // a pseudo-random pattern so the rows don't settle down too quickly
+        let tiles = (0..300)
+            .map(|i: usize| if i.pow(3) % 7 < 3 { '^' } else { '.' })
+            .collect::<String>();
+
+        for width in &[1, 2, 3, 63, 64, 65, 127, 128, 129, 300] {
+            let input = &tiles[..*width];
+            let row = Row::new(input);
+
+            assert_eq!(row.to_string(), input);
+            assert_eq!(safe_tiles(&row, 200), naive_safe_tiles(input, 200));
+        }
+    }
+
+    #[test]
+    fn test_cycles() {
+        for input in &[".^^.^", "^....", "^^^^^^^", "..^^.^^^.^", "^......"] {
+            let row = Row::new(input);
+
+            for rows in 0..100 {
+                assert_eq!(safe_tiles(&row, rows), naive_safe_tiles(input, rows));
+            }
+        }
+
+        assert_eq!(
+            safe_tiles(&Row::new("....."), 1_000_000_000_000),
+            5_000_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_random_rows() {
+        // a linear congruential generator for the tiles, so rows have all sorts of lead-ins before
+        // they start repeating
+        let mut seed = 12345_u64;
+
+        for _ in 0..300 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let width = 1 + (seed >> 60) as usize;
+            let input = (0..width)
+                .map(|i| if seed >> (i + 20) & 1 == 1 { '^' } else { '.' })
+                .collect::<String>();
+            let row = Row::new(&input);
+
+            for rows in 0..80 {
+                assert_eq!(
+                    safe_tiles(&row, rows),
+                    naive_safe_tiles(&input, rows),
+                    "{} {}",
+                    input,
+                    rows
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_trillion_rows() {
+        // the full input takes too long to repeat, but the first 40 tiles repeat every 2046 rows
+        let input = &INPUT[..40];
+        let row = Row::new(input);
+        assert_eq!((0..2046).fold(row.clone(), |row, _| row.next()), row);
+
+        let rows = 1_000_000_000_000;
+        let lap = naive_safe_tiles(input, 2046);
+        let expected = rows / 2046 * lap + naive_safe_tiles(input, rows % 2046);
+
+        assert_eq!(safe_tiles(&row, rows), expected);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(INPUT), 2005);
@@ -1,106 +1,61 @@
+use crate::{
+    compass::{Convention, Direction, ParseDirectionError, Point},
+    grid::Grid,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
-
-pub enum Move {
-    Up,
-    Down,
-    Left,
-    Right,
-}
 
 #[aoc_generator(day2)]
-pub fn generator(input: &str) -> Vec<Vec<Move>> {
+pub fn generator(input: &str) -> Result<Vec<Vec<Direction>>, ParseDirectionError> {
     input
         .trim()
         .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| match c {
-                    'U' => Move::Up,
-                    'D' => Move::Down,
-                    'L' => Move::Left,
-                    'R' => Move::Right,
-                    e => panic!("Unknown move char: {}", e),
-                })
-                .collect()
-        })
+        .map(|l| l.chars().map(|c| c.to_string().parse()).collect())
         .collect()
 }
 
+/// The keypad you expected
+static SQUARE: &str = "123\n456\n789";
+
+/// The keypad actually on the bathroom door, where '.' is a gap with no key
+static DIAMOND: &str = "..1..\n.234.\n56789\n.ABC.\n..D..";
+
+fn keypad(layout: &str) -> Grid<Option<char>> {
+    Grid::parse(layout, |c| if c == '.' { None } else { Some(c) }).unwrap()
+}
+
 #[aoc(day2, part1)]
-pub fn part1(input: &[Vec<Move>]) -> String {
-    let valid_locations = vec![
-        ((0, 0), '1'),
-        ((1, 0), '2'),
-        ((2, 0), '3'),
-        ((0, 1), '4'),
-        ((1, 1), '5'),
-        ((2, 1), '6'),
-        ((0, 2), '7'),
-        ((1, 2), '8'),
-        ((2, 2), '9'),
-    ]
-    .into_iter()
-    .collect::<HashMap<_, _>>();
+pub fn part1(input: &[Vec<Direction>]) -> String {
+    let keypad = keypad(SQUARE);
 
     input
         .iter()
-        .map(|moves| get_key(moves, &valid_locations, 1, 1))
+        .map(|moves| get_key(moves, &keypad, Point::new(1, 1)))
         .collect()
 }
 
 #[aoc(day2, part2)]
-pub fn part2(input: &[Vec<Move>]) -> String {
-    let valid_locations = vec![
-        ((2, 0), '1'),
-        ((1, 1), '2'),
-        ((2, 1), '3'),
-        ((3, 1), '4'),
-        ((0, 2), '5'),
-        ((1, 2), '6'),
-        ((2, 2), '7'),
-        ((3, 2), '8'),
-        ((4, 2), '9'),
-        ((1, 3), 'A'),
-        ((2, 3), 'B'),
-        ((3, 3), 'C'),
-        ((2, 4), 'D'),
-    ]
-    .into_iter()
-    .collect::<HashMap<_, _>>();
+pub fn part2(input: &[Vec<Direction>]) -> String {
+    let keypad = keypad(DIAMOND);
 
     input
         .iter()
-        .map(|moves| get_key(moves, &valid_locations, 0, 2))
+        .map(|moves| get_key(moves, &keypad, Point::new(0, 2)))
         .collect()
 }
 
-fn get_key(moves: &[Move], valid_keys: &HashMap<(usize, usize), char>, x: usize, y: usize) -> char {
-    let mut x = x;
-    let mut y = y;
+fn get_key(moves: &[Direction], keypad: &Grid<Option<char>>, start: Point) -> char {
+    let mut current = start;
 
     for m in moves.iter() {
-        let next_x = match m {
-            Move::Left => x.saturating_sub(1),
-            Move::Right => x + 1,
-            _ => x,
-        };
-
-        let next_y = match m {
-            Move::Up => y.saturating_sub(1),
-            Move::Down => y + 1,
-            _ => y,
-        };
-
-        if valid_keys.contains_key(&(next_x, next_y)) {
-            x = next_x;
-            y = next_y;
+        // the keypad is written top down, so up decreases y
+        let next = current.step(m, Convention::YDown);
+
+        if let Some(Some(_)) = keypad.get(next) {
+            current = next;
         }
     }
 
-    *valid_keys
-        .get(&(x, y))
-        .expect("Ended up at invalid location")
+    keypad[current].expect("Ended up at invalid location")
 }
 
 #[cfg(test)]
@@ -111,13 +66,13 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part1(&input), "99332");
     }
 
     #[test]
     fn test_part2() {
-        let input = generator(INPUT);
+        let input = generator(INPUT).unwrap();
         assert_eq!(part2(&input), "DD483");
     }
 }
@@ -1,3 +1,4 @@
+use crate::{compass::Point, grid::Grid};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::{
@@ -9,8 +10,7 @@ use nom::{
 
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DiskNode {
-    pub x: usize,
-    pub y: usize,
+    pub position: Point<usize>,
     pub size: usize,
     pub used: usize,
     pub available: usize,
@@ -35,8 +35,7 @@ fn parse(s: &str) -> IResult<&str, DiskNode> {
     let (s, percent) = preceded(space1, terminated(digit1, tag("%")))(s)?;
 
     let node = DiskNode {
-        x: x.parse().unwrap(),
-        y: y.parse().unwrap(),
+        position: Point::new(x.parse().unwrap(), y.parse().unwrap()),
         size: size.parse().unwrap(),
         used: used.parse().unwrap(),
         available: available.parse().unwrap(),
@@ -62,7 +61,7 @@ pub fn part1(input: &[DiskNode]) -> usize {
     input
         .iter()
         .cartesian_product(input.iter())
-        .filter(|(a, b)| a.x != b.x || a.y != b.y)
+        .filter(|(a, b)| a.position != b.position)
         .filter(|(a, b)| a.used > 0 && a.used <= b.available)
         .count()
 
@@ -75,7 +74,7 @@ pub fn part1(input: &[DiskNode]) -> usize {
 
     for a in input.iter() {
         for b in input.iter() {
-            if a.x == b.x && a.y == b.y {
+            if a.position == b.position {
                 continue;
             }
 
@@ -88,39 +87,47 @@ pub fn part1(input: &[DiskNode]) -> usize {
     count*/
 }
 
+/// Draws the nodes as the puzzle does, with `_` for the empty node and `#` for the huge ones which
+/// can never be moved
+pub fn render(input: &[DiskNode]) -> Grid<char> {
+    let width = input.iter().map(|n| n.position.x).max().unwrap() + 1;
+    let height = input.iter().map(|n| n.position.y).max().unwrap() + 1;
+    let mut grid = Grid::new(width, height, '.');
+
+    for node in input {
+        let c = if node.used == 0 {
+            '_'
+        } else if node.used > 100 {
+            '#'
+        } else {
+            '.'
+        };
+
+        grid[Point::new(node.position.x as i32, node.position.y as i32)] = c;
+    }
+
+    grid
+}
+
 #[aoc(day22, part2)]
 pub fn part2(input: &[DiskNode]) -> usize {
-    let width = input.iter().map(|x| x.x).max().unwrap();
-    let hole = input.iter().find_position(|n| n.used == 0).unwrap().1;
+    let grid = render(input);
+    let width = grid.width() - 1;
+    let hole = grid.position(|&c| c == '_').unwrap();
+    let hole = Point::new(hole.x as usize, hole.y as usize);
 
-    let wall_edge = input
+    let wall_edge = grid
         .iter()
-        .filter(|n| n.used > 100)
-        .map(|wall| wall.x)
+        .filter(|(_, &c)| c == '#')
+        .map(|(wall, _)| wall.x as usize)
         .min()
         .unwrap();
 
-    /*
-    let height = input.iter().map(|x| x.y).max().unwrap();
-
-    use std::collections::HashMap;
-    let nodes: HashMap<(usize, usize), &DiskNode> = input.iter().map(|n| ((n.x, n.y), n)).collect();
-
-    for y in 0..height+1 {
-        for x in 0..width+1 {
-            let node = nodes.get(&(x, y)).unwrap();
-            let c = if node.used == 0 { '_' } else if node.used > 100 { '#' } else { '.' };
-            print!("{}", c);
-        }
-        println!();
-    }
-    */
-
     // move the hole left until it can go around the wall, and back right again later (so 2x)
     let navigate_wall = (hole.x - wall_edge + 1) * 2;
 
     // move the hole to the top right (which nudges the target data one to the left)
-    let move_to_target = hole.y + (width - hole.x);
+    let move_to_target = hole.manhattan(&Point::new(width, 0));
 
     // follow a 5-move pattern to shift the target data one to the left (minus 1 since it already moved once)
     let top_row_pattern = 5 * (width - 1);
@@ -141,6 +148,15 @@ mod tests {
         assert_eq!(part1(&input), 872)
     }
 
+    #[test]
+    fn test_render() {
+        let grid = render(&generator(INPUT));
+
+        assert_eq!(grid.row(0)[0], '.');
+        assert_eq!(grid.iter().filter(|(_, &c)| c == '_').count(), 1);
+        assert!(grid.column(0).all(|&c| c != '#'));
+    }
+
     #[test]
     fn test_part2() {
         let input = generator(INPUT);
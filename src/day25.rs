@@ -1,44 +1,94 @@
-use aoc_runner_derive::aoc;
+use crate::assembunny::{
+    parse_program, Computer, Instruction, OutputSink, ParseError, RuntimeError, Value,
+};
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::HashSet;
 
 /*
-I assumed this wouldn't be one that can be brute-forced but turns out
-it could've been since the result is small enough.
-
 See the working out in the day25.txt input file to decipher what
-the original program is doing.
+the original program is doing, or run it through assembunny::decompile.
 
 It basically creates an input number and then divides it by 2, printing
 out the remainder each time (0 or 1) until it hits 0, then loops forever.
+
+Rather than relying on that, the real program is run and paused after every
+output. If the computer ever gets back to a state it was in after a previous
+output then everything from there on must repeat, so the signal is infinite.
+A program which goes too long without any output at all isn't a clock either.
 */
 
+/// The most steps the program can take between outputs before it's given up on
+const MAX_STEPS_BETWEEN_OUTPUTS: usize = 1_000_000;
+
+/// Checks each output continues the 0, 1, 0, 1... clock signal
+struct ClockSignal {
+    expected: Value,
+    valid: bool,
+}
+
+impl OutputSink for ClockSignal {
+    fn write(&mut self, value: Value) -> bool {
+        self.valid = value == self.expected;
+        self.expected = 1 - self.expected;
+
+        // pause after every output so the state can be checked
+        false
+    }
+}
+
+#[aoc_generator(day25)]
+pub fn generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_program(input)
+}
+
 #[aoc(day25, part1)]
-pub fn part1(_input: &str) -> usize {
-    for a in 1.. {
-        let d = a + (14 * 182);
-        let output = run_program(d);
+pub fn part1(input: &[Instruction]) -> Result<Value, RuntimeError> {
+    let mut a = 1;
 
-        if output == "010101010101" {
-            return a;
-        }
+    while !is_clock_signal(input, a)? {
+        a += 1;
     }
 
-    unreachable!()
+    Ok(a)
 }
 
-fn run_program(d: usize) -> String {
-    let mut output = String::new();
-    let mut a = d;
+/// Run the program with the given initial value of `a` and check it produces an infinite clock signal
+fn is_clock_signal(input: &[Instruction], a: Value) -> Result<bool, RuntimeError> {
+    let mut program = input.to_vec();
+    let mut computer = Computer::with_a(a);
+    let mut signal = ClockSignal {
+        expected: 0,
+        valid: true,
+    };
+    let mut seen = HashSet::new();
 
-    while a != 0 {
-        let c = 2 - (a % 2);
-        a /= 2;
+    loop {
+        // run until the next output, which pauses the computer
+        let mut steps = 0;
 
-        let b = 2 - c;
+        while computer.step(&mut program, &mut signal)? {
+            steps += 1;
 
-        output.push_str(&b.to_string());
-    }
+            if steps > MAX_STEPS_BETWEEN_OUTPUTS {
+                return Ok(false);
+            }
+        }
+
+        if !signal.valid || computer.is_halted(&program) {
+            return Ok(false);
+        }
+
+        let state = (
+            computer.pointer,
+            computer.registers.clone(),
+            signal.expected,
+            program.clone(),
+        );
 
-    output
+        if !seen.insert(state) {
+            return Ok(true);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +99,22 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 182);
+        let input = generator(INPUT).unwrap();
+        assert_eq!(part1(&input), Ok(182));
+    }
+
+    #[test]
+    fn test_is_clock_signal() {
+        let input = generator("out a\ninc a\nout a\ndec a\njnz 1 -4").unwrap();
+        assert_eq!(is_clock_signal(&input, 0), Ok(true));
+        assert_eq!(is_clock_signal(&input, 1), Ok(false));
+
+        // finite signals don't count
+        let input = generator("out a\ninc a\nout a").unwrap();
+        assert_eq!(is_clock_signal(&input, 0), Ok(false));
+
+        // neither do signals which start well but then go quiet, even if they never halt
+        let input = generator("out a\ninc a\nout a\ninc b\njnz 1 -1").unwrap();
+        assert_eq!(is_clock_signal(&input, 0), Ok(false));
     }
 }
@@ -1,4 +1,9 @@
+use crate::{
+    grid::Grid,
+    ocr::{self, OcrError},
+};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::{fmt, fs, io, path::Path};
 
 #[derive(Debug, PartialEq)]
 pub enum Instruction {
@@ -36,79 +41,161 @@ pub fn generator(input: &str) -> Vec<Instruction> {
         .collect()
 }
 
-fn build_grid(input: &[Instruction]) -> [[bool; 50]; 6] {
-    let mut grid = [[false; 50]; 6];
+/// The size of the screen on the door
+pub const WIDTH: usize = 50;
+pub const HEIGHT: usize = 6;
 
-    for instruction in input {
-        match instruction {
-            Instruction::Rectangle { x, y } => {
-                (0..*y).for_each(|y| {
-                    for x in 0..*x {
-                        grid[y][x] = true;
-                    }
-                });
-            }
-            Instruction::RotateRow { row, delta } => {
-                let current = grid[*row];
-                let mut next = [false; 50];
-                let len = next.len();
-
-                (0..len).for_each(|i| {
-                    let index = (i + delta + len) % len;
-                    next[index] = current[i];
-                });
-
-                for (col, v) in next.iter().enumerate() {
-                    grid[*row][col] = *v;
-                }
-            }
+impl fmt::Display for Instruction {
+    /// Writes the instruction as it appears in the puzzle input
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Rectangle { x, y } => write!(f, "rect {}x{}", x, y),
+            Instruction::RotateRow { row, delta } => write!(f, "rotate row y={} by {}", row, delta),
             Instruction::RotateColumn { col, delta } => {
-                let current = grid.iter().map(|row| row[*col]).collect::<Vec<_>>();
-                let mut next = [false; 6];
-                let len = next.len();
-
-                (0..len).for_each(|i| {
-                    let index = (i + delta + len) % len;
-                    next[index] = current[i];
-                });
+                write!(f, "rotate column x={} by {}", col, delta)
+            }
+        }
+    }
+}
 
-                for (row, v) in next.iter().enumerate() {
-                    grid[row][*col] = *v;
-                }
+/// Apply an instruction to the screen, panicking if it refers to a row or column off the screen
+pub fn apply(grid: &mut Grid<bool>, instruction: &Instruction) {
+    match *instruction {
+        Instruction::Rectangle { x, y } => {
+            for y in 0..y {
+                grid.row_mut(y)[..x]
+                    .iter_mut()
+                    .for_each(|cell| *cell = true);
             }
         }
+        Instruction::RotateRow { row, delta } => grid.rotate_row(row, delta),
+        Instruction::RotateColumn { col, delta } => grid.rotate_column(col, delta),
+    }
+}
+
+/// The screen after all the instructions have been applied
+pub fn build_grid(input: &[Instruction], width: usize, height: usize) -> Grid<bool> {
+    let mut grid = Grid::new(width, height, false);
+
+    for instruction in input {
+        apply(&mut grid, instruction);
     }
 
     grid
 }
 
-#[aoc(day8, part1)]
-pub fn part1(input: &[Instruction]) -> usize {
-    let grid = build_grid(input);
+/// The screen after each instruction in turn
+pub fn frames(
+    input: &[Instruction],
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = Grid<bool>> + '_ {
+    input
+        .iter()
+        .scan(Grid::new(width, height, false), |grid, instruction| {
+            apply(grid, instruction);
+            Some(grid.clone())
+        })
+}
+
+/// Formats for exporting a frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// `#` for lit pixels and `.` for unlit ones
+    Text,
 
-    grid.iter()
-        .map(|row| row.iter().filter(|&cell| *cell).count())
-        .sum()
+    /// Plain (ASCII) portable bitmap, where 1 is black so lit pixels are drawn as 0
+    Pbm,
+
+    /// Plain (ASCII) portable graymap, with lit pixels at full brightness
+    Pgm,
 }
 
-#[aoc(day8, part2)]
-pub fn part2(_input: &[Instruction]) -> String {
-    // uncomment to see this properly
+impl Format {
+    /// The usual file extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Text => "txt",
+            Format::Pbm => "pbm",
+            Format::Pgm => "pgm",
+        }
+    }
 
-    /*
-    let grid = build_grid(input);
+    pub fn export(self, grid: &Grid<bool>) -> String {
+        let pixels = |on: &str, off: &str| {
+            grid.rows()
+                .map(|row| {
+                    row.iter()
+                        .map(|&lit| if lit { on } else { off })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
 
-    for y in 0..6 {
-        for x in 0..50 {
-            let c = if grid[y][x] { '*' } else { ' ' };
-            print!("{}", c);
+        match self {
+            Format::Text => grid.map(|&lit| if lit { '#' } else { '.' }).to_string(),
+            Format::Pbm => format!(
+                "P1\n{} {}\n{}\n",
+                grid.width(),
+                grid.height(),
+                pixels("0", "1")
+            ),
+            Format::Pgm => format!(
+                "P2\n{} {}\n255\n{}\n",
+                grid.width(),
+                grid.height(),
+                pixels("255", "0")
+            ),
         }
+    }
+}
+
+/// A text log of every instruction followed by the screen after it was applied
+pub fn animation_log(input: &[Instruction], width: usize, height: usize) -> String {
+    input
+        .iter()
+        .zip(frames(input, width, height))
+        .enumerate()
+        .map(|(i, (instruction, frame))| {
+            format!(
+                "{}: {}\n{}",
+                i + 1,
+                instruction,
+                Format::Text.export(&frame)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        println!();
+/// Write each frame to its own numbered file in the directory, e.g. `frame-0001.pbm`
+pub fn export_frames(
+    input: &[Instruction],
+    width: usize,
+    height: usize,
+    format: Format,
+    directory: &Path,
+) -> io::Result<()> {
+    for (i, frame) in frames(input, width, height).enumerate() {
+        let path = directory.join(format!("frame-{:04}.{}", i + 1, format.extension()));
+        fs::write(path, format.export(&frame))?;
     }
-    */
 
-    "CFLELOYFCS".into()
+    Ok(())
+}
+
+#[aoc(day8, part1)]
+pub fn part1(input: &[Instruction]) -> usize {
+    let grid = build_grid(input, WIDTH, HEIGHT);
+
+    grid.iter().filter(|(_, &on)| on).count()
+}
+
+#[aoc(day8, part2)]
+pub fn part2(input: &[Instruction]) -> Result<String, OcrError> {
+    ocr::recognise(&build_grid(input, WIDTH, HEIGHT))
 }
 
 #[cfg(test)]
@@ -126,7 +213,58 @@ mod tests {
     #[test]
     fn test_part2() {
         let input = generator(INPUT);
-        assert_eq!(part2(&input), "CFLELOYFCS");
+        assert_eq!(part2(&input), Ok("CFLELOYFCS".to_string()));
+    }
+
+    static EXAMPLE: &str = "rect 3x2
+rotate column x=1 by 1
+rotate row y=0 by 4
+rotate column x=1 by 1";
+
+    #[test]
+    fn test_frames() {
+        let input = generator(EXAMPLE);
+        let rendered = frames(&input, 7, 3)
+            .map(|frame| Format::Text.export(&frame))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "###....\n###....\n.......\n",
+                "#.#....\n###....\n.#.....\n",
+                "....#.#\n###....\n.#.....\n",
+                ".#..#.#\n#.#....\n.#.....\n",
+            ]
+        );
+
+        assert_eq!(
+            build_grid(&input, 7, 3),
+            frames(&input, 7, 3).last().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let input = generator(EXAMPLE);
+        let grid = build_grid(&input[..1], 4, 2);
+
+        assert_eq!(Format::Pbm.export(&grid), "P1\n4 2\n0 0 0 1\n0 0 0 1\n");
+        assert_eq!(
+            Format::Pgm.export(&grid),
+            "P2\n4 2\n255\n255 255 255 0\n255 255 255 0\n"
+        );
+
+        let log = animation_log(&input[..2], 7, 3);
+        assert!(log.starts_with("1: rect 3x2\n###....\n"));
+        assert!(log.contains("\n2: rotate column x=1 by 1\n#.#....\n"));
+    }
+
+    #[test]
+    fn test_display() {
+        for line in EXAMPLE.lines() {
+            assert_eq!(generator(line)[0].to_string(), line);
+        }
     }
 
     #[test]
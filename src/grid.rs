@@ -0,0 +1,361 @@
+use crate::compass::Point;
+use std::{
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// A dense rectangular grid, stored a row at a time with (0, 0) in the top left
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GridError {
+    /// The line (starting from 1) has a different length to the first line
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} cells but expected {}",
+                line, found, expected
+            ),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+impl<T: Clone> Grid<T> {
+    /// Create a grid with every cell set to the same value
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Rotate a row to the right, wrapping around
+    ///
+    /// Panics if the row is outside the grid, like [`Grid::row`]
+    pub fn rotate_row(&mut self, y: usize, by: usize) {
+        assert!(y < self.height, "Row {} is outside the grid", y);
+
+        let start = y * self.width;
+        self.cells[start..start + self.width].rotate_right(by % self.width.max(1));
+    }
+
+    /// Rotate a column down, wrapping around
+    ///
+    /// Panics if the column is outside the grid, like [`Grid::column`]
+    pub fn rotate_column(&mut self, x: usize, by: usize) {
+        let column = self.column(x).cloned().collect::<Vec<_>>();
+
+        for (y, value) in column.into_iter().enumerate() {
+            let y = (y + by) % self.height;
+            self.cells[y * self.width + x] = value;
+        }
+    }
+
+    /// Rotate the whole grid 90° clockwise
+    pub fn rotate_clockwise(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| (0..self.height).rev().map(move |y| (x, y)))
+            .map(|(x, y)| self.cells[y * self.width + x].clone())
+            .collect();
+
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Rotate the whole grid 90° anticlockwise
+    pub fn rotate_anticlockwise(&self) -> Self {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+            .map(|(x, y)| self.cells[y * self.width + x].clone())
+            .collect();
+
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a grid a line at a time, converting each character to a cell
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            let found = cells.len() - before;
+
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(GridError::Ragged {
+                        line: i + 1,
+                        expected,
+                        found,
+                    })
+                }
+                Some(_) => {}
+            }
+
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Convert a point to an index into the cells, if it's inside the grid
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (point.x as usize, point.y as usize);
+
+        if point.x >= 0 && point.y >= 0 && x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// The cell at the point, or None if it's outside the grid
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(move |i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Panics if the column is outside the grid, rather than wrapping onto the next row
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {} is outside the grid", x);
+
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// Every point in the grid, a row at a time
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+    }
+
+    /// Every cell along with its point, a row at a time
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The point of the first cell which matches, searching a row at a time
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The horizontal and vertical neighbours which are inside the grid
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.cells_at(point.neighbours_4())
+    }
+
+    /// The horizontal, vertical and diagonal neighbours which are inside the grid
+    pub fn neighbours_8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.cells_at(point.neighbours_8())
+    }
+
+    fn cells_at<const N: usize>(&self, points: [Point; N]) -> impl Iterator<Item = (Point, &T)> {
+        IntoIterator::into_iter(points).filter_map(move |p| self.get(p).map(|cell| (p, cell)))
+    }
+
+    /// Convert every cell, e.g. to characters for displaying
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, |c| c)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the point is outside the grid, see [`Grid::get`] for a checked version
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("Point outside the grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("Point outside the grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Renders each row on its own line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static GRID: &str = "abc\ndef";
+
+    #[test]
+    fn test_parse() {
+        let grid = GRID.parse::<Grid<char>>().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(1, 1)), Some(&'e'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid[Point::new(2, 0)], 'c');
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        assert_eq!(
+            "ab\nc".parse::<Grid<char>>(),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let digits = Grid::parse("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+        assert_eq!(digits.iter().map(|(_, d)| d).sum::<u32>(), 10);
+    }
+
+    #[test]
+    fn test_views() {
+        let mut grid = GRID.parse::<Grid<char>>().unwrap();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.position(|&c| c == 'f'), Some(Point::new(2, 1)));
+
+        grid.row_mut(0)[0] = 'A';
+        grid[Point::new(1, 0)] = 'B';
+        assert_eq!(grid.to_string(), "ABc\ndef\n");
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut grid = GRID.parse::<Grid<char>>().unwrap();
+
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_anticlockwise().to_string(),
+            grid.to_string()
+        );
+
+        grid.rotate_row(0, 4);
+        assert_eq!(grid.to_string(), "cab\ndef\n");
+
+        grid.rotate_column(2, 1);
+        assert_eq!(grid.to_string(), "caf\ndeb\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 3 is outside the grid")]
+    fn test_rotate_column_outside() {
+        let mut grid = GRID.parse::<Grid<char>>().unwrap();
+        grid.rotate_column(3, 1);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = GRID.parse::<Grid<char>>().unwrap();
+
+        let corner = grid
+            .neighbours_4(Point::new(0, 0))
+            .map(|(_, &c)| c)
+            .collect::<String>();
+        assert_eq!(corner, "bd");
+
+        let middle = grid
+            .neighbours_8(Point::new(1, 1))
+            .map(|(_, &c)| c)
+            .collect::<String>();
+        assert_eq!(middle, "abcdf");
+    }
+
+    #[test]
+    fn test_map() {
+        let mut grid = Grid::new(3, 2, false);
+        grid[Point::new(1, 1)] = true;
+
+        let rendered = grid.map(|&on| if on { '#' } else { '.' });
+        assert_eq!(rendered.to_string(), "...\n.#.\n");
+    }
+}
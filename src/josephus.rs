@@ -0,0 +1,334 @@
+//! Variations on the Josephus problem, where people in a circle are eliminated one at a time
+//!
+//! Everyone is numbered from 1, and person 1 goes first.
+
+/// The survivor when every person eliminates the next person along, i.e. every second person
+/// is eliminated
+///
+/// Writing `n` as `2^m + l`, after `l` eliminations there are `2^m` people left and it's person
+/// `2l + 1`'s turn, and with a power of two people the person who goes first always wins
+pub fn steal_next(n: usize) -> usize {
+    assert!(n > 0, "There must be at least one person");
+
+    let power = 1 << (usize::BITS - 1 - n.leading_zeros());
+    2 * (n - power) + 1
+}
+
+/// The survivor when every person eliminates the person directly across the circle (or the
+/// nearer of the two people across it if there's an even number of people)
+///
+/// Writing `n` as `3^m + l`, the survivor is `n` if `l` is 0, `l` while `l <= 3^m` and then
+/// counts up in twos
+pub fn steal_across(n: usize) -> usize {
+    assert!(n > 0, "There must be at least one person");
+
+    let mut power: usize = 1;
+    while let Some(next) = power.checked_mul(3).filter(|&next| next <= n) {
+        power = next;
+    }
+
+    if n == power {
+        n
+    } else if n - power <= power {
+        n - power
+    } else {
+        // 2n - 3 * 3^m, arranged so nothing overflows
+        (n - power) + (n - 2 * power)
+    }
+}
+
+/// The survivor when counting round the circle and eliminating every `k`th person
+///
+/// Takes O(k log n) steps by eliminating a whole lap's worth of people at a time while there are
+/// more than `k` left
+pub fn survivor(n: usize, k: usize) -> usize {
+    assert!(n > 0, "There must be at least one person");
+    assert!(k > 0, "The step must be at least one");
+
+    survivor_from_zero(n, k) + 1
+}
+
+/// Same as [`survivor`], but numbering people from 0
+///
+/// Each lap only depends on the size of the circle after it, so the sizes are worked out going
+/// forwards and then the survivor's position is worked out going backwards through them
+fn survivor_from_zero(n: usize, k: usize) -> usize {
+    if k == 1 {
+        return n - 1;
+    }
+
+    // eliminate everyone at a multiple of k in each lap while there's more than one of them
+    let mut laps = Vec::new();
+    let mut n = n;
+
+    while n >= k {
+        laps.push(n);
+        n -= n / k;
+    }
+
+    // only one person is eliminated each lap now, so use the standard recurrence
+    let mut survivor = 0;
+
+    for i in 2..=n {
+        survivor = (survivor + k % i) % i;
+    }
+
+    // the smaller circle starts just after the last elimination of the lap, so work out where its
+    // survivor was in the bigger one
+    for n in laps.into_iter().rev() {
+        survivor = if survivor < n % k {
+            // in the part of the lap after the last elimination
+            survivor + n - n % k
+        } else {
+            let survivor = survivor - n % k;
+            survivor + survivor / (k - 1)
+        };
+    }
+
+    survivor
+}
+
+/// Who eliminates whom
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Count round the circle and eliminate the `k`th person, starting again from the next person
+    Step(usize),
+
+    /// Eliminate the person directly across the circle, then it's the next person's turn
+    Across,
+}
+
+/// The people in the order they're eliminated, leaving just the survivor
+///
+/// Keeps track of who's left in a Fenwick tree, so each elimination takes O(log n)
+pub struct Eliminations {
+    /// Fenwick tree counting the people left, indexed from 1
+    tree: Vec<usize>,
+    remaining: usize,
+
+    /// Index among the people left of whoever's turn it is
+    turn: usize,
+    rule: Rule,
+}
+
+impl Eliminations {
+    pub fn new(n: usize, rule: Rule) -> Self {
+        assert!(rule != Rule::Step(0), "The step must be at least one");
+
+        // every person is still in, so each node covers its whole range
+        let tree = (0..=n).map(|i| i & i.wrapping_neg()).collect();
+
+        Eliminations {
+            tree,
+            remaining: n,
+            turn: 0,
+            rule,
+        }
+    }
+
+    /// Find the person at the given index (from 0) among the people left
+    fn person_at(&self, index: usize) -> usize {
+        let mut position = 0;
+        let mut index = index;
+        let mut step = (self.tree.len() - 1).next_power_of_two();
+
+        while step > 0 {
+            let next = position + step;
+
+            if next < self.tree.len() && self.tree[next] <= index {
+                position = next;
+                index -= self.tree[next];
+            }
+
+            step /= 2;
+        }
+
+        position + 1
+    }
+
+    fn remove(&mut self, person: usize) {
+        let mut i = person;
+
+        while i < self.tree.len() {
+            self.tree[i] -= 1;
+            i += i & i.wrapping_neg();
+        }
+
+        self.remaining -= 1;
+    }
+}
+
+impl Iterator for Eliminations {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining <= 1 {
+            return None;
+        }
+
+        let (eliminated, turn) = match self.rule {
+            Rule::Step(k) => {
+                // the next person along takes the eliminated person's index
+                let eliminated = (self.turn + (k - 1) % self.remaining) % self.remaining;
+                (eliminated, eliminated)
+            }
+            Rule::Across => {
+                // removing someone before the current person shifts them back one
+                let eliminated = (self.turn + self.remaining / 2) % self.remaining;
+                let next = if eliminated > self.turn {
+                    self.turn + 1
+                } else {
+                    self.turn
+                };
+                (eliminated, next)
+            }
+        };
+
+        let person = self.person_at(eliminated);
+        self.remove(person);
+        self.turn = turn % self.remaining;
+
+        Some(person)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.remaining.saturating_sub(1);
+        (left, Some(left))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Pass people from the front of the queue to the back, eliminating every kth
+    fn simulate_step(n: usize, k: usize) -> Vec<usize> {
+        let mut people = (1..=n).collect::<VecDeque<_>>();
+        let mut eliminated = Vec::new();
+
+        while people.len() > 1 {
+            people.rotate_left((k - 1) % people.len());
+            eliminated.push(people.pop_front().unwrap());
+        }
+
+        eliminated.extend(people);
+        eliminated
+    }
+
+    /// Eliminate the person across the circle by keeping each half in its own queue
+    fn simulate_across(n: usize) -> Vec<usize> {
+        let mut right = (1..n.div_ceil(2) + 1).collect::<VecDeque<_>>();
+        let mut left = (n.div_ceil(2) + 1..(n + 1)).collect::<VecDeque<_>>();
+        let mut eliminated = Vec::new();
+
+        while !left.is_empty() {
+            if right.len() > left.len() {
+                eliminated.push(right.pop_back().unwrap());
+            } else {
+                eliminated.push(left.pop_front().unwrap());
+            }
+
+            if let Some(person) = left.pop_front() {
+                right.push_back(person);
+                left.push_back(right.pop_front().unwrap());
+            }
+        }
+
+        eliminated.extend(right);
+        eliminated
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(steal_next(5), 3);
+        assert_eq!(steal_across(5), 2);
+        assert_eq!(survivor(41, 3), 31);
+    }
+
+    #[test]
+    fn test_closed_forms() {
+        for n in 1..500 {
+            assert_eq!(steal_next(n), *simulate_step(n, 2).last().unwrap(), "{}", n);
+            assert_eq!(
+                steal_across(n),
+                *simulate_across(n).last().unwrap(),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_survivor() {
+        for n in 1..200 {
+            for k in 1..20 {
+                let expected = *simulate_step(n, k).last().unwrap();
+                assert_eq!(survivor(n, k), expected, "{} {}", n, k);
+            }
+        }
+
+        assert_eq!(survivor(3_012_210, 2), steal_next(3_012_210));
+    }
+
+    #[test]
+    fn test_large_steps() {
+        // the standard recurrence, one person at a time
+        let recurrence = |n: usize, k: usize| (2..=n).fold(0, |r, i| (r + k % i) % i) + 1;
+
+        for &(n, k) in &[(10_000_000, 1_000_000), (3_000_000, 5_000_000), (1000, 999)] {
+            assert_eq!(survivor(n, k), recurrence(n, k), "{} {}", n, k);
+        }
+
+        assert_eq!(survivor(1, usize::MAX), 1);
+        assert_eq!(survivor(2, usize::MAX), recurrence(2, usize::MAX));
+    }
+
+    #[test]
+    fn test_closed_forms_overflow() {
+        // the same closed form, but with room to spare
+        let across = |n: u128| {
+            let mut power = 1;
+            while power * 3 <= n {
+                power *= 3;
+            }
+
+            match n - power {
+                0 => n,
+                l if l <= power => l,
+                _ => 2 * n - 3 * power,
+            }
+        };
+
+        for &n in &[usize::MAX, usize::MAX - 1, usize::MAX / 3 * 2 + 7] {
+            assert_eq!(steal_across(n) as u128, across(n as u128), "{}", n);
+        }
+
+        assert_eq!(steal_next(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_eliminations() {
+        assert_eq!(
+            Eliminations::new(7, Rule::Step(3)).collect::<Vec<_>>(),
+            vec![3, 6, 2, 7, 5, 1]
+        );
+        assert_eq!(
+            Eliminations::new(5, Rule::Across).collect::<Vec<_>>(),
+            vec![3, 5, 1, 4]
+        );
+        assert_eq!(Eliminations::new(1, Rule::Across).count(), 0);
+
+        for n in 1..100 {
+            for k in (1..10).chain([usize::MAX]) {
+                let expected = simulate_step(n, k);
+                let order = Eliminations::new(n, Rule::Step(k)).collect::<Vec<_>>();
+                assert_eq!(order, expected[..n - 1]);
+            }
+
+            let expected = simulate_across(n);
+            let order = Eliminations::new(n, Rule::Across).collect::<Vec<_>>();
+            assert_eq!(order, expected[..n - 1]);
+        }
+    }
+}
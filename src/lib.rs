@@ -1,14 +1,16 @@
 use aoc_runner_derive::aoc_lib;
+use std::fmt;
 
-mod compass;
+pub mod assembunny;
+pub mod compass;
 mod day1;
 mod day10;
-mod day11;
+pub mod day11;
 mod day12;
 mod day13;
 mod day14;
 mod day15;
-mod day16;
+pub mod day16;
 mod day17;
 mod day18;
 mod day19;
@@ -24,12 +26,27 @@ mod day4;
 mod day5;
 mod day6;
 mod day7;
-mod day8;
+pub mod day8;
 mod day9;
+pub mod grid;
+pub mod josephus;
+pub mod number_theory;
+pub mod ocr;
+pub mod runner;
+pub mod search;
 aoc_lib! { year = 2016 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Part {
     One,
     Two,
 }
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::{error::Error, fmt};
+
+/// The greatest common divisor of `a` and `b` along with Bézout coefficients `x` and `y`, such
+/// that `a * x + b * y == gcd`
+///
+/// The divisor is never negative
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn gcd(a: i128, b: i128) -> i128 {
+    extended_gcd(a, b).0
+}
+
+pub fn lcm(a: i128, b: i128) -> i128 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// The `x` in `0..modulus` where `a * x ≡ 1 (mod modulus)`, which only exists if `a` and the
+/// modulus are coprime
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (gcd, x, _) = extended_gcd(a, modulus);
+
+    if gcd == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+/// The set of numbers `x` where `x ≡ remainder (mod modulus)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Congruence {
+    /// Always in `0..modulus`, so it's also the smallest non-negative solution
+    pub remainder: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    /// Panics if the modulus isn't positive
+    pub fn new(remainder: i128, modulus: i128) -> Self {
+        assert!(modulus > 0, "Modulus must be positive, got {}", modulus);
+
+        Congruence {
+            remainder: remainder.rem_euclid(modulus),
+            modulus,
+        }
+    }
+
+    /// The numbers which satisfy both congruences
+    ///
+    /// The moduli don't need to be coprime, e.g. `x ≡ 1 (mod 4)` and `x ≡ 3 (mod 6)` combine to
+    /// `x ≡ 9 (mod 12)`, but `x ≡ 0 (mod 4)` and `x ≡ 1 (mod 6)` have no solutions
+    pub fn combine(self, other: Congruence) -> Result<Congruence, CongruenceError> {
+        let (gcd, x, _) = extended_gcd(self.modulus, other.modulus);
+        let difference = other.remainder - self.remainder;
+
+        if difference % gcd != 0 {
+            return Err(CongruenceError::NoSolution);
+        }
+
+        // self.remainder + self.modulus * k satisfies the other congruence when
+        // k ≡ (difference / gcd) * (self.modulus / gcd)⁻¹ (mod other.modulus / gcd), and x is that
+        // inverse from Bézout's identity
+        let step = other.modulus / gcd;
+        let k = mul_mod(
+            (difference / gcd).rem_euclid(step),
+            x.rem_euclid(step),
+            step,
+        );
+        let modulus = self
+            .modulus
+            .checked_mul(step)
+            .ok_or(CongruenceError::Overflow)?;
+
+        // k < step, so this is below the new modulus and can't overflow
+        Ok(Congruence::new(self.remainder + self.modulus * k, modulus))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CongruenceError {
+    /// No number satisfies every congruence
+    NoSolution,
+
+    /// The combined modulus doesn't fit in an i128
+    Overflow,
+}
+
+impl fmt::Display for CongruenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CongruenceError::NoSolution => write!(f, "the congruences have no common solution"),
+            CongruenceError::Overflow => write!(f, "the combined modulus is too big"),
+        }
+    }
+}
+
+impl Error for CongruenceError {}
+
+/// `a * b % modulus` for `a` and `b` in `0..modulus`, without overflowing even when the product
+/// doesn't fit in an i128
+fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // double and add, where everything is below 2^127 so sums of two fit in a u128
+    let modulus = modulus as u128;
+    let (mut a, mut b) = (a as u128, b as u128);
+    let mut product = 0;
+
+    while b > 0 {
+        if b & 1 == 1 {
+            product = (product + a) % modulus;
+        }
+
+        a = (a + a) % modulus;
+        b >>= 1;
+    }
+
+    product as i128
+}
+
+/// Solve a system of congruences with the Chinese remainder theorem, giving every number which
+/// satisfies them all as a single congruence
+pub fn crt(
+    congruences: impl IntoIterator<Item = Congruence>,
+) -> Result<Congruence, CongruenceError> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), Congruence::combine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd() {
+        for &(a, b) in &[(240, 46), (46, 240), (-12, 18), (17, 5), (0, 7), (7, 0)] {
+            let (gcd, x, y) = extended_gcd(a, b);
+
+            assert!(gcd >= 0);
+            assert_eq!(a * x + b * y, gcd);
+            assert_eq!(a % gcd.max(1), 0);
+            assert_eq!(b % gcd.max(1), 0);
+        }
+
+        assert_eq!(gcd(240, 46), 2);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(4, 6), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let solution = crt(vec![
+            Congruence::new(2, 3),
+            Congruence::new(3, 5),
+            Congruence::new(2, 7),
+        ]);
+        assert_eq!(solution, Ok(Congruence::new(23, 105)));
+
+        // not coprime
+        assert_eq!(
+            crt(vec![Congruence::new(1, 4), Congruence::new(3, 6)]),
+            Ok(Congruence::new(9, 12))
+        );
+        assert_eq!(
+            crt(vec![Congruence::new(0, 4), Congruence::new(1, 6)]),
+            Err(CongruenceError::NoSolution)
+        );
+
+        assert_eq!(crt(vec![]), Ok(Congruence::new(0, 1)));
+    }
+
+    #[test]
+    fn test_crt_brute_force() {
+        for m1 in 1..12 {
+            for m2 in 1..12 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let expected = (0..lcm(m1, m2)).find(|x| x % m1 == r1 && x % m2 == r2);
+                        let solution = Congruence::new(r1, m1).combine(Congruence::new(r2, m2));
+
+                        assert_eq!(solution.ok().map(|c| c.remainder), expected);
+                        assert!(solution.is_err() || solution.unwrap().modulus == lcm(m1, m2));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        let primes = [1_000_000_007, 998_244_353, 2_147_483_647];
+        let solution = crt(primes.iter().map(|&p| Congruence::new(p - 1, p))).unwrap();
+
+        // -1 modulo every prime
+        assert_eq!(solution.modulus, primes.iter().product::<i128>());
+        assert_eq!(solution.remainder, solution.modulus - 1);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // five primes around 10^9 have a product around 10^45, far more than an i128 can hold
+        let primes = [
+            1_000_000_007,
+            998_244_353,
+            2_147_483_647,
+            1_000_000_009,
+            999_999_937,
+        ];
+        let congruences = primes.iter().map(|&p| Congruence::new(p - 1, p));
+        assert_eq!(crt(congruences), Err(CongruenceError::Overflow));
+
+        // the combined modulus fits, but the intermediate products are too big for an i128
+        let big = (1 << 100) + 1;
+        let solution = Congruence::new(1, 2)
+            .combine(Congruence::new(big - 2, big))
+            .unwrap();
+
+        assert_eq!(solution.modulus, 2 * big);
+        assert_eq!(solution.remainder % 2, 1);
+        assert_eq!(solution.remainder % big, big - 2);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::grid::Grid;
+use std::{error::Error, fmt};
+
+/// Width of each letter on the screen, including the blank column which separates them
+pub const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 6;
+
+/// The block letters used by the puzzles, each drawn as 6 rows of 5 pixels
+static FONT: &[(char, &str)] = &[
+    ('A', ".##..#..#.#..#.####.#..#.#..#."),
+    ('B', "###..#..#.###..#..#.#..#.###.."),
+    ('C', ".##..#..#.#....#....#..#..##.."),
+    ('E', "####.#....###..#....#....####."),
+    ('F', "####.#....###..#....#....#...."),
+    ('G', ".##..#..#.#....#.##.#..#..###."),
+    ('H', "#..#.#..#.####.#..#.#..#.#..#."),
+    ('I', ".###...#....#....#....#...###."),
+    ('J', "..##....#....#....#.#..#..##.."),
+    ('K', "#..#.#.#..##...#.#..#.#..#..#."),
+    ('L', "#....#....#....#....#....####."),
+    ('O', ".##..#..#.#..#.#..#.#..#..##.."),
+    ('P', "###..#..#.#..#.###..#....#...."),
+    ('R', "###..#..#.#..#.###..#.#..#..#."),
+    ('S', ".###.#....#.....##.....#.###.."),
+    ('U', "#..#.#..#.#..#.#..#.#..#..##.."),
+    ('Y', "#...##...#.#.#...#....#....#.."),
+    ('Z', "####....#...#...#...#....####."),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OcrError {
+    /// The screen can't be split into whole letters
+    Size { width: usize, height: usize },
+
+    /// The letters which weren't in the font, as their position and drawing
+    Unrecognised(Vec<(usize, String)>),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Size { width, height } => write!(
+                f,
+                "a {}x{} screen can't be split into {}x{} letters",
+                width, height, GLYPH_WIDTH, GLYPH_HEIGHT
+            ),
+            OcrError::Unrecognised(glyphs) => {
+                write!(f, "{} unrecognised letter(s)", glyphs.len())?;
+
+                for (index, bitmap) in glyphs {
+                    write!(f, "\n\nletter {}:", index)?;
+
+                    for row in bitmap.as_bytes().chunks(GLYPH_WIDTH) {
+                        write!(f, "\n{}", String::from_utf8_lossy(row))?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Read the letters displayed on a screen, where `true` is a lit pixel
+pub fn recognise(screen: &Grid<bool>) -> Result<String, OcrError> {
+    if screen.height() != GLYPH_HEIGHT || !screen.width().is_multiple_of(GLYPH_WIDTH) {
+        return Err(OcrError::Size {
+            width: screen.width(),
+            height: screen.height(),
+        });
+    }
+
+    let mut text = String::new();
+    let mut unrecognised = Vec::new();
+
+    for index in 0..screen.width() / GLYPH_WIDTH {
+        let bitmap = (0..GLYPH_HEIGHT)
+            .flat_map(|y| &screen.row(y)[index * GLYPH_WIDTH..(index + 1) * GLYPH_WIDTH])
+            .map(|&on| if on { '#' } else { '.' })
+            .collect::<String>();
+
+        match FONT.iter().find(|(_, glyph)| *glyph == bitmap) {
+            Some((letter, _)) => text.push(*letter),
+            None => unrecognised.push((index, bitmap)),
+        }
+    }
+
+    if unrecognised.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::Unrecognised(unrecognised))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(rows: &str) -> Grid<bool> {
+        Grid::parse(rows, |c| c == '#').unwrap()
+    }
+
+    #[test]
+    fn test_font() {
+        for (letter, glyph) in FONT {
+            assert_eq!(glyph.len(), GLYPH_WIDTH * GLYPH_HEIGHT);
+
+            let rows = glyph
+                .as_bytes()
+                .chunks(GLYPH_WIDTH)
+                .map(|row| String::from_utf8_lossy(row))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            assert_eq!(recognise(&screen(&rows)), Ok(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_recognise() {
+        let hi = screen(
+            "#..#..###.\n\
+             #..#...#..\n\
+             ####...#..\n\
+             #..#...#..\n\
+             #..#...#..\n\
+             #..#..###.",
+        );
+
+        assert_eq!(recognise(&hi), Ok("HI".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            recognise(&Grid::new(7, 6, false)),
+            Err(OcrError::Size {
+                width: 7,
+                height: 6
+            })
+        );
+
+        let mut blank = Grid::new(10, 6, false);
+        blank.row_mut(0)[0] = true;
+
+        let error = recognise(&blank).unwrap_err();
+        assert_eq!(
+            error,
+            OcrError::Unrecognised(vec![
+                (0, format!("#{}", ".".repeat(29))),
+                (1, ".".repeat(30))
+            ])
+        );
+        assert!(error
+            .to_string()
+            .starts_with("2 unrecognised letter(s)\n\nletter 0:\n#....\n"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::*;
+use std::error::Error;
+
+/// The printable answer from a solution, or why it couldn't be found
+pub type Answer = Result<String, Box<dyn Error>>;
+
+/// A single part of a day, solving it from the raw puzzle input
+pub struct Solution {
+    pub day: u8,
+    pub part: Part,
+    pub solve: fn(&str) -> Answer,
+}
+
+impl Solution {
+    const fn new(day: u8, part: Part, solve: fn(&str) -> Answer) -> Self {
+        Solution { day, part, solve }
+    }
+}
+
+/// Converts the different types the solutions return into an [`Answer`]
+pub trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+macro_rules! display_answer {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Answer {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_answer!(i32, i64, u32, usize, String);
+
+impl<T: IntoAnswer> IntoAnswer for Option<T> {
+    fn into_answer(self) -> Answer {
+        self.ok_or("no answer found")?.into_answer()
+    }
+}
+
+impl<T: IntoAnswer, E: Error + 'static> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Answer {
+        self?.into_answer()
+    }
+}
+
+/// Wraps a part in a function from the raw input, parsing it with the day's generator first if
+/// there is one (followed by `?` if the generator can fail)
+macro_rules! solve {
+    ($day:ident::$part:ident) => {
+        |input| $day::$part(input).into_answer()
+    };
+    ($day:ident::$part:ident, $generator:ident) => {
+        |input| $day::$part(&$day::$generator(input)).into_answer()
+    };
+    ($day:ident::$part:ident, $generator:ident?) => {
+        |input| $day::$part(&$day::$generator(input)?).into_answer()
+    };
+}
+
+/// Every solution, in order
+pub static SOLUTIONS: &[Solution] = &[
+    Solution::new(1, Part::One, solve!(day1::part1, generator)),
+    Solution::new(1, Part::Two, solve!(day1::part2, generator)),
+    Solution::new(2, Part::One, solve!(day2::part1, generator?)),
+    Solution::new(2, Part::Two, solve!(day2::part2, generator?)),
+    Solution::new(3, Part::One, solve!(day3::part1)),
+    Solution::new(3, Part::Two, solve!(day3::part2)),
+    Solution::new(4, Part::One, solve!(day4::part1, generator)),
+    Solution::new(4, Part::Two, solve!(day4::part2, generator)),
+    Solution::new(5, Part::One, solve!(day5::part1)),
+    Solution::new(5, Part::Two, solve!(day5::part2)),
+    Solution::new(6, Part::One, solve!(day6::part1)),
+    Solution::new(6, Part::Two, solve!(day6::part2)),
+    Solution::new(7, Part::One, solve!(day7::part1)),
+    Solution::new(7, Part::Two, solve!(day7::part2)),
+    Solution::new(8, Part::One, solve!(day8::part1, generator)),
+    Solution::new(8, Part::Two, solve!(day8::part2, generator)),
+    Solution::new(9, Part::One, solve!(day9::part1)),
+    Solution::new(9, Part::Two, solve!(day9::part2)),
+    Solution::new(10, Part::One, solve!(day10::part1, generator)),
+    Solution::new(10, Part::Two, solve!(day10::part2, generator)),
+    Solution::new(11, Part::One, solve!(day11::part1, generator?)),
+    Solution::new(11, Part::Two, solve!(day11::part2, generator?)),
+    Solution::new(12, Part::One, solve!(day12::part1, generator?)),
+    Solution::new(12, Part::Two, solve!(day12::part2, generator?)),
+    Solution::new(13, Part::One, solve!(day13::part1, generator?)),
+    Solution::new(13, Part::Two, solve!(day13::part2, generator?)),
+    Solution::new(14, Part::One, solve!(day14::part1)),
+    Solution::new(14, Part::Two, solve!(day14::part2)),
+    Solution::new(15, Part::One, solve!(day15::part1, generator?)),
+    Solution::new(15, Part::Two, solve!(day15::part2, generator?)),
+    Solution::new(16, Part::One, solve!(day16::part1)),
+    Solution::new(16, Part::Two, solve!(day16::part2)),
+    Solution::new(17, Part::One, solve!(day17::part1)),
+    Solution::new(17, Part::Two, solve!(day17::part2)),
+    Solution::new(18, Part::One, solve!(day18::part1)),
+    Solution::new(18, Part::Two, solve!(day18::part2)),
+    Solution::new(19, Part::One, solve!(day19::part1)),
+    Solution::new(19, Part::Two, solve!(day19::part2)),
+    Solution::new(20, Part::One, solve!(day20::part1, generator)),
+    Solution::new(20, Part::Two, solve!(day20::part2, generator)),
+    Solution::new(21, Part::One, solve!(day21::part1, generator)),
+    Solution::new(21, Part::Two, solve!(day21::part2, generator)),
+    Solution::new(22, Part::One, solve!(day22::part1, generator)),
+    Solution::new(22, Part::Two, solve!(day22::part2, generator)),
+    Solution::new(23, Part::One, solve!(day23::part1, generator?)),
+    Solution::new(23, Part::Two, solve!(day23::part2, generator?)),
+    Solution::new(24, Part::One, solve!(day24::part1, generator?)),
+    Solution::new(24, Part::Two, solve!(day24::part2, generator?)),
+    Solution::new(25, Part::One, solve!(day25::part1, generator?)),
+];
+
+/// Look up the solution for a single part of a day
+pub fn find(day: u8, part: Part) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|s| s.day == day && s.part == part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let solution = find(1, Part::Two).unwrap();
+        let input = include_str!("../input/2016/day1.txt");
+        assert_eq!((solution.solve)(input).unwrap(), "159");
+
+        assert!(find(25, Part::Two).is_none());
+        assert!(find(26, Part::One).is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        let solution = find(13, Part::One).unwrap();
+        assert_eq!(
+            (solution.solve)("not a number").unwrap_err().to_string(),
+            "invalid digit found in string"
+        );
+
+        let solution = find(12, Part::One).unwrap();
+        assert_eq!(
+            (solution.solve)("abc").unwrap_err().to_string(),
+            "line 1, column 1: unexpected `abc`, expected cpy x r or inc r or dec r or jnz x x or \
+             tgl x or out x"
+        );
+
+        assert_eq!(
+            None::<usize>.into_answer().unwrap_err().to_string(),
+            "no answer found"
+        );
+    }
+}
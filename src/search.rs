@@ -0,0 +1,384 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// A space of states which can be searched, defined by the states reachable from each one
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    /// All the states reachable in a single move from the given state
+    fn neighbours(&self, node: &Self::Node) -> Vec<Self::Node>;
+
+    /// The cost of moving between two neighbouring states, only used by [`dijkstra`] and [`astar`]
+    fn cost(&self, _from: &Self::Node, _to: &Self::Node) -> usize {
+        1
+    }
+}
+
+/// A route through a graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    /// Every node along the route, including the start and the goal
+    pub nodes: Vec<N>,
+
+    /// The total cost of the moves, which is the number of moves for the breadth-first searches
+    pub cost: usize,
+}
+
+impl<N> Path<N> {
+    /// The number of moves along the route
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// The node the route ends at
+    pub fn goal(&self) -> &N {
+        self.nodes.last().unwrap()
+    }
+}
+
+/// Breadth-first search for the path with the fewest moves to a goal node
+pub fn bfs<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+) -> Option<Path<G::Node>> {
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    parents.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(unweighted(reconstruct(node, |n| parents[n].clone())));
+        }
+
+        for neighbour in graph.neighbours(&node) {
+            if !parents.contains_key(&neighbour) {
+                parents.insert(neighbour.clone(), Some(node.clone()));
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search for the number of moves to every node within `max_steps` of the start
+pub fn distances<G: Graph>(graph: &G, start: G::Node, max_steps: usize) -> HashMap<G::Node, usize> {
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    distances.insert(start.clone(), 0);
+    queue.push_back((start, 0));
+
+    while let Some((node, steps)) = queue.pop_front() {
+        if steps == max_steps {
+            continue;
+        }
+
+        for neighbour in graph.neighbours(&node) {
+            if !distances.contains_key(&neighbour) {
+                distances.insert(neighbour.clone(), steps + 1);
+                queue.push_back((neighbour, steps + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Breadth-first search from both ends at once, meeting in the middle
+///
+/// Only valid when every move can be reversed, i.e. `a` is a neighbour of `b` whenever `b` is a
+/// neighbour of `a`
+pub fn bidirectional_bfs<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+) -> Option<Path<G::Node>> {
+    if start == goal {
+        return Some(unweighted(vec![start]));
+    }
+
+    let mut forward = HashMap::new();
+    let mut backward = HashMap::new();
+    forward.insert(start.clone(), None);
+    backward.insert(goal.clone(), None);
+
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![goal];
+
+    // the two sides have explored disjoint nodes until they meet, so the first meeting point is
+    // on a shortest path
+    let meeting = loop {
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return None;
+        }
+
+        // always grow the smaller frontier
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            expand(graph, &mut forward_frontier, &mut forward, &backward)
+        } else {
+            expand(graph, &mut backward_frontier, &mut backward, &forward)
+        };
+
+        if let Some(meeting) = meeting {
+            break meeting;
+        }
+    };
+
+    let mut nodes = reconstruct(meeting.clone(), |n| forward[n].clone());
+    let mut node = meeting;
+
+    while let Some(next) = backward[&node].clone() {
+        nodes.push(next.clone());
+        node = next;
+    }
+
+    Some(unweighted(nodes))
+}
+
+/// Move the frontier on by one level, returning a node which has already been reached by the
+/// other side if there is one
+fn expand<G: Graph>(
+    graph: &G,
+    frontier: &mut Vec<G::Node>,
+    parents: &mut HashMap<G::Node, Option<G::Node>>,
+    other: &HashMap<G::Node, Option<G::Node>>,
+) -> Option<G::Node> {
+    let mut next = Vec::new();
+
+    for node in frontier.iter() {
+        for neighbour in graph.neighbours(node) {
+            if parents.contains_key(&neighbour) {
+                continue;
+            }
+
+            parents.insert(neighbour.clone(), Some(node.clone()));
+
+            if other.contains_key(&neighbour) {
+                return Some(neighbour);
+            }
+
+            next.push(neighbour);
+        }
+    }
+
+    *frontier = next;
+    None
+}
+
+/// Search for the cheapest path to a goal node using the costs from [`Graph::cost`]
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+) -> Option<Path<G::Node>> {
+    astar(graph, start, is_goal, |_| 0)
+}
+
+/// Search for the cheapest path to a goal node, exploring the nodes which the heuristic
+/// estimates are closest to a goal first
+///
+/// The path is only guaranteed to be the cheapest if the heuristic never overestimates the
+/// remaining cost
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+    heuristic: impl Fn(&G::Node) -> usize,
+) -> Option<Path<G::Node>> {
+    let mut best = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    best.insert(start.clone(), (0, None));
+    queue.push(Queued {
+        priority: heuristic(&start),
+        cost: 0,
+        node: start,
+    });
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        // a cheaper way here was found after this was queued
+        if cost > best[&node].0 {
+            continue;
+        }
+
+        if is_goal(&node) {
+            let nodes = reconstruct(node, |n| best[n].1.clone());
+            return Some(Path { nodes, cost });
+        }
+
+        for neighbour in graph.neighbours(&node) {
+            let next = cost + graph.cost(&node, &neighbour);
+
+            if best.get(&neighbour).is_none_or(|&(c, _)| next < c) {
+                best.insert(neighbour.clone(), (next, Some(node.clone())));
+                queue.push(Queued {
+                    priority: next + heuristic(&neighbour),
+                    cost: next,
+                    node: neighbour,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Follow the parents back from the given node to the start
+fn reconstruct<N>(node: N, parent: impl Fn(&N) -> Option<N>) -> Vec<N> {
+    let mut nodes = vec![node];
+
+    while let Some(next) = parent(nodes.last().unwrap()) {
+        nodes.push(next);
+    }
+
+    nodes.reverse();
+    nodes
+}
+
+fn unweighted<N>(nodes: Vec<N>) -> Path<N> {
+    let cost = nodes.len() - 1;
+    Path { nodes, cost }
+}
+
+/// A node waiting in the priority queue, ordered so that the lowest priority is popped first
+struct Queued<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority).reverse()
+    }
+}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compass::Point;
+
+    /// A 5x5 grid with a wall down the middle except at the bottom, where moving right costs 5
+    struct Walled;
+
+    impl Walled {
+        fn is_open(point: &Point) -> bool {
+            (0..5).contains(&point.x) && (0..5).contains(&point.y) && (point.x != 2 || point.y == 4)
+        }
+    }
+
+    impl Graph for Walled {
+        type Node = Point;
+
+        fn neighbours(&self, node: &Point) -> Vec<Point> {
+            if !Walled::is_open(node) {
+                return Vec::new();
+            }
+
+            node.neighbours_4()
+                .iter()
+                .copied()
+                .filter(Walled::is_open)
+                .collect()
+        }
+
+        fn cost(&self, from: &Point, to: &Point) -> usize {
+            if to.x > from.x {
+                5
+            } else {
+                1
+            }
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs(&Walled, Point::new(0, 0), |p| *p == Point::new(4, 0)).unwrap();
+
+        assert_eq!(path.steps(), 12);
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.first(), Some(&Point::new(0, 0)));
+        assert_eq!(path.goal(), &Point::new(4, 0));
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|w| Walled.neighbours(&w[0]).contains(&w[1])));
+
+        assert_eq!(bfs(&Walled, Point::new(0, 0), |p| p.x > 4), None);
+    }
+
+    #[test]
+    fn test_bidirectional_bfs() {
+        for goal in &[
+            Point::new(0, 0),
+            Point::new(1, 0),
+            Point::new(4, 0),
+            Point::new(3, 3),
+        ] {
+            let path = bidirectional_bfs(&Walled, Point::new(0, 0), *goal).unwrap();
+            let expected = bfs(&Walled, Point::new(0, 0), |p| p == goal).unwrap();
+
+            assert_eq!(path.steps(), expected.steps());
+            assert_eq!(path.goal(), goal);
+            assert!(path
+                .nodes
+                .windows(2)
+                .all(|w| Walled.neighbours(&w[0]).contains(&w[1])));
+        }
+
+        assert_eq!(
+            bidirectional_bfs(&Walled, Point::new(0, 0), Point::new(2, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // moving right is expensive, so it's cheaper to go right at the top and come back down
+        let path = dijkstra(&Walled, Point::new(4, 4), |p| *p == Point::new(0, 0)).unwrap();
+        assert_eq!(path.cost, 8);
+
+        let path = dijkstra(&Walled, Point::new(0, 0), |p| *p == Point::new(4, 0)).unwrap();
+        assert_eq!(path.cost, 4 * 5 + 8);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = Point::new(4, 0);
+        let heuristic = |p: &Point| ((goal.x - p.x).abs() + (goal.y - p.y).abs()) as usize;
+
+        let path = astar(&Walled, Point::new(0, 0), |p| *p == goal, heuristic).unwrap();
+        let expected = dijkstra(&Walled, Point::new(0, 0), |p| *p == goal).unwrap();
+        assert_eq!(path.cost, expected.cost);
+    }
+
+    #[test]
+    fn test_distances() {
+        let distances = distances(&Walled, Point::new(0, 0), 3);
+
+        assert_eq!(distances.len(), 7);
+        assert_eq!(distances[&Point::new(1, 2)], 3);
+        assert!(!distances.contains_key(&Point::new(1, 3)));
+    }
+}